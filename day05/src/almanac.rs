@@ -0,0 +1,115 @@
+use std::{fmt, path::Path, str::FromStr};
+
+use aoclib::input::parse_two_phase;
+
+use crate::{map::Map, Error};
+
+/// A complete almanac: a seeds line, followed by each of its maps in application order.
+///
+/// `Seeds` is generic because the seeds line means different things in each part;
+/// see [`crate::seeds::Seeds`] and [`crate::seed_ranges::SeedRanges`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac<Seeds> {
+    pub seeds: Seeds,
+    pub maps: Vec<Map>,
+}
+
+impl<Seeds> Almanac<Seeds>
+where
+    Seeds: FromStr<Err = Error>,
+{
+    pub fn load(input: &Path) -> Result<Self, Error> {
+        let (seeds, maps) = parse_two_phase::<Seeds, Map>(input)?;
+        let maps = maps.collect();
+        Ok(Self { seeds, maps })
+    }
+}
+
+/// Writes the almanac in the puzzle's text format, such that [`Almanac::load`] reads it back unchanged.
+impl<Seeds> fmt::Display for Almanac<Seeds>
+where
+    Seeds: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.seeds)?;
+        for map in &self.maps {
+            writeln!(f)?;
+            write!(f, "{map}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        seed_ranges::{SeedRange, SeedRanges},
+        seeds::Seeds,
+        strategies::map,
+    };
+    use proptest::prelude::*;
+    use std::io::Write as _;
+    use tempfile::NamedTempFile;
+
+    fn seeds() -> impl Strategy<Value = Seeds> {
+        prop::collection::vec(0_i64..10_000_000_000, 1..20).prop_map(Seeds)
+    }
+
+    fn seed_ranges() -> impl Strategy<Value = SeedRanges> {
+        prop::collection::vec((0_i64..10_000_000_000, 1_i64..1_000_000), 1..20).prop_map(|ranges| {
+            SeedRanges(
+                ranges
+                    .into_iter()
+                    .map(|(start, length)| SeedRange { start, length })
+                    .collect(),
+            )
+        })
+    }
+
+    fn round_trip<Seeds>(almanac: &Almanac<Seeds>) -> Almanac<Seeds>
+    where
+        Seeds: fmt::Display + FromStr<Err = Error>,
+    {
+        let mut tempfile = NamedTempFile::new().unwrap();
+        write!(tempfile.as_file_mut(), "{almanac}").unwrap();
+        let parsed = Almanac::load(tempfile.path()).unwrap();
+        tempfile.close().unwrap();
+        parsed
+    }
+
+    proptest! {
+        #[test]
+        fn map_round_trips(map in map()) {
+            let parsed = map.to_string().parse::<Map>().unwrap();
+            prop_assert_eq!(parsed, map);
+        }
+
+        #[test]
+        fn seeds_round_trip(seeds in seeds()) {
+            let parsed = seeds.to_string().parse::<Seeds>().unwrap();
+            prop_assert_eq!(parsed, seeds);
+        }
+
+        #[test]
+        fn seed_ranges_round_trip(seed_ranges in seed_ranges()) {
+            let parsed = seed_ranges.to_string().parse::<SeedRanges>().unwrap();
+            prop_assert_eq!(parsed, seed_ranges);
+        }
+
+        #[test]
+        fn almanac_round_trips(seeds in seeds(), maps in prop::collection::vec(map(), 1..8)) {
+            let almanac = Almanac { seeds, maps };
+            prop_assert_eq!(round_trip(&almanac), almanac);
+        }
+
+        #[test]
+        fn almanac_with_ranges_round_trips(
+            seeds in seed_ranges(),
+            maps in prop::collection::vec(map(), 1..8),
+        ) {
+            let almanac = Almanac { seeds, maps };
+            prop_assert_eq!(round_trip(&almanac), almanac);
+        }
+    }
+}
@@ -1,41 +1,49 @@
 use std::path::Path;
 
-use aoclib::input::parse_two_phase;
+use almanac::Almanac;
 use map::Map;
-use seed_ranges::SeedRanges;
+use map_entry::MapEntry;
+use seed_ranges::{SeedRange, SeedRanges};
 use seeds::Seeds;
 
+mod almanac;
+mod lint;
 mod map;
 mod map_entry;
 mod seed_ranges;
 mod seeds;
+#[cfg(test)]
+mod strategies;
+mod trace;
+mod verify;
+
+pub use lint::lint;
+pub use trace::{trace, TraceTarget};
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    let (seeds, maps) = parse_two_phase::<Seeds, Map>(input)?;
-    let maps = maps.collect::<Vec<_>>();
+    let Almanac { seeds, maps } = Almanac::<Seeds>::load(input)?;
     // note: we depend on the input file's map ordering being appropriate, allowing a direct pass-through.
 
-    let lowest_location = seeds
+    let locations = seeds
         .0
         .iter()
         .copied()
         .map(|mut value| {
             for map in &maps {
-                value = map.apply(value);
+                value = map.apply(value)?;
             }
-            value
+            Ok(value)
         })
-        .min()
-        .ok_or(Error::NoSolution)?;
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let lowest_location = locations.into_iter().min().ok_or(Error::NoSolution)?;
 
     println!("lowest location (pt 1): {lowest_location}");
     Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let (ranges, maps) = parse_two_phase::<SeedRanges, Map>(input)?;
-    let mut ranges = ranges.0;
-
+/// Find the lowest location reachable from any seed in `ranges`, by mapping whole ranges at each stage.
+fn lowest_location_by_ranges(maps: &[Map], mut ranges: Vec<SeedRange>) -> Result<i64, Error> {
     // note: we depend on the input file's map ordering being appropriate,
     // allowing us to funnel the output of one stage directly into the input of
     // the next.
@@ -44,21 +52,50 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         let mut next_ranges = Vec::with_capacity(ranges.len());
 
         for range in ranges {
-            next_ranges.extend(map.apply_range(range));
+            next_ranges.extend(map.apply_range(range)?);
         }
 
         ranges = next_ranges;
     }
 
-    let lowest_location = ranges
+    ranges
         .iter()
         .map(|range| range.start)
         .min()
-        .ok_or(Error::NoSolution)?;
+        .ok_or(Error::NoSolution)
+}
+
+pub fn part2(input: &Path) -> Result<(), Error> {
+    let Almanac { seeds, maps } = Almanac::<SeedRanges>::load(input)?;
+    let lowest_location = lowest_location_by_ranges(&maps, seeds.0)?;
     println!("lowest location (pt 2): {lowest_location}");
     Ok(())
 }
 
+/// Cross-check part 2 by brute force: map every seed individually, across all available threads.
+///
+/// This is slow; for real inputs, expect it to take minutes rather than milliseconds.
+pub fn verify_part2(input: &Path) -> Result<(), Error> {
+    let Almanac { seeds, maps } = Almanac::<SeedRanges>::load(input)?;
+    let by_ranges = lowest_location_by_ranges(&maps, seeds.0.clone())?;
+    let brute_force = verify::lowest_location_brute_force(&maps, &seeds.0)?;
+    if by_ranges != brute_force {
+        return Err(Error::VerificationFailed {
+            by_ranges,
+            brute_force,
+        });
+    }
+    println!("verified lowest location (pt 2): {brute_force}");
+    Ok(())
+}
+
+/// Write the almanac back out in the puzzle's format, with each map's entries sorted.
+pub fn write_normalized(input: &Path, output: &Path) -> Result<(), Error> {
+    let almanac = Almanac::<Seeds>::load(input)?;
+    std::fs::write(output, almanac.to_string())?;
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -72,6 +109,10 @@ pub enum Error {
         output1: i64,
         output2: i64,
     },
+    #[error("arithmetic overflow in map {name} at entry [{entry}]")]
+    Overflow { name: String, entry: MapEntry },
+    #[error("range-based lowest location {by_ranges} disagrees with brute force {brute_force}")]
+    VerificationFailed { by_ranges: i64, brute_force: i64 },
     #[error("no solution found")]
     NoSolution,
 }
@@ -0,0 +1,269 @@
+use std::{ops::Range, path::Path, str::FromStr};
+
+use aoclib::input::parse_two_phase;
+
+use crate::{map::Map, map_entry::MapEntry, seeds::Seeds, Error};
+
+/// Something suspicious about a map which is worth a human's attention.
+///
+/// Only [`Finding::Overlap`] causes validation to fail; the others are
+/// accepted silently by [`Map::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// Two entries claim the same inputs and map them to different outputs.
+    Overlap {
+        left: MapEntry,
+        right: MapEntry,
+        inputs: Range<i64>,
+    },
+    /// Two entries claim the same inputs, but agree on their outputs.
+    ContiguousOverlap {
+        left: MapEntry,
+        right: MapEntry,
+        inputs: Range<i64>,
+    },
+    /// No entry covers these inputs, so they fall back to the identity mapping.
+    Gap { inputs: Range<i64> },
+    /// Distinct inputs from two entries map onto the same outputs.
+    DestinationCollision {
+        left: MapEntry,
+        right: MapEntry,
+        outputs: Range<i64>,
+    },
+    /// The entry's source or destination range is not representable.
+    Overflow { entry: MapEntry },
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Overlap {
+                left,
+                right,
+                inputs,
+            } => write!(
+                f,
+                "ambiguous overlap: [{left}] and [{right}] both map inputs {inputs:?}"
+            ),
+            Finding::ContiguousOverlap {
+                left,
+                right,
+                inputs,
+            } => write!(
+                f,
+                "contiguous overlap: [{left}] and [{right}] agree on inputs {inputs:?}"
+            ),
+            Finding::Gap { inputs } => write!(f, "gap: inputs {inputs:?} map to themselves"),
+            Finding::DestinationCollision {
+                left,
+                right,
+                outputs,
+            } => write!(
+                f,
+                "destination collision: [{left}] and [{right}] both produce outputs {outputs:?}"
+            ),
+            Finding::Overflow { entry } => write!(f, "overflow: [{entry}] is not representable"),
+        }
+    }
+}
+
+fn intersection(a: Range<i64>, b: Range<i64>) -> Option<Range<i64>> {
+    let start = a.start.max(b.start);
+    let end = a.end.min(b.end);
+    (start < end).then_some(start..end)
+}
+
+impl Map {
+    /// Report every overlap, gap, and destination collision in this map.
+    ///
+    /// Unlike validation, this does not stop at the first problem.
+    pub fn lint(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        // overflowing entries can't meaningfully be compared with anything else, so we
+        // report them and then exclude them from the rest of the analysis
+        let mut entries = Vec::with_capacity(self.entries().len());
+        for entry in self.entries() {
+            match (entry.source_end(), entry.destination_end()) {
+                (Some(source_end), Some(destination_end)) => entries.push((
+                    entry,
+                    entry.source_start..source_end,
+                    entry.destination_start..destination_end,
+                )),
+                _ => findings.push(Finding::Overflow { entry: *entry }),
+            }
+        }
+
+        // gaps: entries are sorted by `source_start`, so we only need to track the
+        // highest source end seen so far
+        let mut covered_until: Option<i64> = None;
+        for (_entry, sources, _destinations) in &entries {
+            if let Some(end) = covered_until.filter(|&end| end < sources.start) {
+                findings.push(Finding::Gap {
+                    inputs: end..sources.start,
+                });
+            }
+            covered_until = Some(covered_until.map_or(sources.end, |end| end.max(sources.end)));
+        }
+
+        // overlaps and collisions: there are only ~40 entries per map, so pairwise is fine
+        for (idx, (left, left_sources, left_destinations)) in entries.iter().enumerate() {
+            for (right, right_sources, right_destinations) in &entries[idx + 1..] {
+                let source_overlap = intersection(left_sources.clone(), right_sources.clone());
+                if let Some(inputs) = source_overlap {
+                    if left.apply(inputs.start) == right.apply(inputs.start) {
+                        findings.push(Finding::ContiguousOverlap {
+                            left: **left,
+                            right: **right,
+                            inputs,
+                        });
+                        // when the entries agree, the destinations overlap for exactly the shared
+                        // inputs, so there is no separate collision to report
+                        continue;
+                    }
+                    findings.push(Finding::Overlap {
+                        left: **left,
+                        right: **right,
+                        inputs,
+                    });
+                }
+
+                if let Some(outputs) =
+                    intersection(left_destinations.clone(), right_destinations.clone())
+                {
+                    findings.push(Finding::DestinationCollision {
+                        left: **left,
+                        right: **right,
+                        outputs,
+                    });
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// A `Map` which has not been validated, so that problems can be reported rather than rejected.
+struct UnvalidatedMap(Map);
+
+impl FromStr for UnvalidatedMap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Map::parse_unvalidated(s).map(Self)
+    }
+}
+
+/// Print every finding for every map in the almanac.
+pub fn lint(input: &Path) -> Result<(), Error> {
+    let (_seeds, maps) = parse_two_phase::<Seeds, UnvalidatedMap>(input)?;
+
+    let mut total_findings = 0;
+    for UnvalidatedMap(map) in maps {
+        let findings = map.lint();
+        total_findings += findings.len();
+        if findings.is_empty() {
+            println!("{} map: ok", map.name());
+            continue;
+        }
+        println!("{} map:", map.name());
+        for finding in findings {
+            println!("  {finding}");
+        }
+    }
+
+    println!("total findings: {total_findings}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(destination_start: i64, source_start: i64, range_length: i64) -> MapEntry {
+        MapEntry {
+            destination_start,
+            source_start,
+            range_length,
+        }
+    }
+
+    #[test]
+    fn clean_map_has_no_findings() {
+        let map = Map::new_unvalidated("clean", [entry(50, 98, 2), entry(52, 50, 48)]);
+        assert_eq!(map.lint(), Vec::new());
+    }
+
+    #[test]
+    fn reports_every_overlap() {
+        // all three entries overlap one another, which validation would reject at the first pair
+        let map = Map::new_unvalidated(
+            "overlaps",
+            [entry(100, 0, 10), entry(200, 5, 10), entry(300, 8, 10)],
+        );
+        let overlaps = map
+            .lint()
+            .into_iter()
+            .filter_map(|finding| match finding {
+                Finding::Overlap { inputs, .. } => Some(inputs),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(overlaps, vec![5..10, 8..10, 8..15]);
+    }
+
+    #[test]
+    fn reports_contiguous_overlap() {
+        let map = Map::new_unvalidated("contiguous", [entry(10, 0, 5), entry(13, 3, 5)]);
+        assert_eq!(
+            map.lint(),
+            vec![Finding::ContiguousOverlap {
+                left: entry(10, 0, 5),
+                right: entry(13, 3, 5),
+                inputs: 3..5,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_gaps() {
+        let map = Map::new_unvalidated(
+            "gaps",
+            [entry(100, 0, 10), entry(200, 5, 2), entry(300, 12, 3)],
+        );
+        let gaps = map
+            .lint()
+            .into_iter()
+            .filter(|finding| matches!(finding, Finding::Gap { .. }))
+            .collect::<Vec<_>>();
+        assert_eq!(gaps, vec![Finding::Gap { inputs: 10..12 }]);
+    }
+
+    #[test]
+    fn reports_destination_collision() {
+        let map = Map::new_unvalidated("collision", [entry(10, 0, 5), entry(12, 20, 5)]);
+        assert_eq!(
+            map.lint(),
+            vec![
+                Finding::Gap { inputs: 5..20 },
+                Finding::DestinationCollision {
+                    left: entry(10, 0, 5),
+                    right: entry(12, 20, 5),
+                    outputs: 12..15,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_overflow() {
+        let map = Map::new_unvalidated("overflow", [entry(i64::MAX, 0, 2), entry(10, 5, 5)]);
+        assert_eq!(
+            map.lint(),
+            vec![Finding::Overflow {
+                entry: entry(i64::MAX, 0, 2)
+            }]
+        );
+    }
+}
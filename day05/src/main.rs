@@ -1,5 +1,5 @@
 use aoclib::{config::Config, website::get_input};
-use day05::{part1, part2};
+use day05::{lint, part1, part2, trace, verify_part2, write_normalized, TraceTarget};
 
 use clap::Parser;
 use color_eyre::eyre::Result;
@@ -22,6 +22,22 @@ struct RunArgs {
     /// run part 2
     #[arg(long)]
     part2: bool,
+
+    /// cross-check part 2 against a multi-threaded brute force (slow!)
+    #[arg(long)]
+    verify: bool,
+
+    /// report every overlap, gap, and destination collision in the almanac's maps
+    #[arg(long)]
+    lint: bool,
+
+    /// write the almanac back out with normalized maps to this path
+    #[arg(long)]
+    normalize: Option<PathBuf>,
+
+    /// print each stage's value for a seed (`79`) or half-open seed range (`79..93`)
+    #[arg(long)]
+    trace: Option<TraceTarget>,
 }
 
 impl RunArgs {
@@ -44,11 +60,23 @@ fn main() -> Result<()> {
     let args = RunArgs::parse();
     let input_path = args.input()?;
 
+    if args.lint {
+        lint(&input_path)?;
+    }
+    if let Some(output) = &args.normalize {
+        write_normalized(&input_path, output)?;
+    }
+    if let Some(target) = args.trace {
+        trace(&input_path, target)?;
+    }
     if !args.no_part1 {
         part1(&input_path)?;
     }
     if args.part2 {
         part2(&input_path)?;
     }
+    if args.verify {
+        verify_part2(&input_path)?;
+    }
     Ok(())
 }
@@ -1,24 +1,58 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use crate::{map_entry::MapEntry, seed_ranges::SeedRange, Error};
 
-#[derive(Debug)]
+/// One piece of a seed range, as split by [`Map::split_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub input: SeedRange,
+    /// `None` when no entry covers the input, so the identity mapping applies.
+    pub entry: Option<MapEntry>,
+    pub output: SeedRange,
+}
+
+impl Segment {
+    fn identity(range: SeedRange) -> Self {
+        Self {
+            input: range,
+            entry: None,
+            output: range,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Map {
     name: String,
     entries: Vec<MapEntry>,
 }
 
 impl Map {
+    fn overflow(&self, entry: &MapEntry) -> Error {
+        Error::Overflow {
+            name: self.name.clone(),
+            entry: *entry,
+        }
+    }
+
     /// Both validate that there are no ambiguous inputs, and apply the internal precondition that entries are sorted by `source_start`.
+    ///
+    /// This also ensures that every entry's source and destination ranges are representable.
     fn validate(&mut self) -> Result<(), Error> {
         self.entries.sort_by_key(|entry| entry.source_start);
+        for entry in &self.entries {
+            if entry.source_end().is_none() || entry.destination_end().is_none() {
+                return Err(self.overflow(entry));
+            }
+        }
         for window in self.entries.windows(2) {
             let [left, right] = TryInto::<[_; 2]>::try_into(window)
                 .expect("`windows(2)` always produces a window of size 2");
-            if left.source_end() > right.source_start {
+            if left.contains(right.source_start) {
                 let input = right.source_start;
-                let output1 = left.apply(input);
-                let output2 = right.apply(input);
+                // both entries contain the input, and neither destination range overflows
+                let output1 = left.apply(input).ok_or_else(|| self.overflow(&left))?;
+                let output2 = right.apply(input).ok_or_else(|| self.overflow(&right))?;
                 if output1 == output2 {
                     // technically the ranges overlapped, but they formed a contiguous whole,
                     // so there's no ambiguity after all
@@ -41,24 +75,88 @@ impl Map {
         name: impl Into<String>,
         entries: impl IntoIterator<Item = MapEntry>,
     ) -> Result<Self, Error> {
-        let name = name.into();
-        let entries = entries.into_iter().collect();
-        let mut map = Map { name, entries };
+        let mut map = Self::new_unvalidated(name, entries);
         map.validate()?;
         Ok(map)
     }
 
-    pub fn apply(&self, value: i64) -> i64 {
+    /// Construct a map without rejecting ambiguous overlaps.
+    ///
+    /// Entries are still sorted by `source_start`, but `apply` and `apply_range` are
+    /// not meaningful for a map which would fail validation; this exists so that
+    /// such maps can be inspected, i.e. by [`Map::lint`].
+    pub fn new_unvalidated(
+        name: impl Into<String>,
+        entries: impl IntoIterator<Item = MapEntry>,
+    ) -> Self {
+        let name = name.into();
+        let mut entries = entries.into_iter().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.source_start);
+        Map { name, entries }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// This map's entries, sorted by `source_start`.
+    pub fn entries(&self) -> &[MapEntry] {
+        &self.entries
+    }
+
+    /// Parse a map from its text block without validating it.
+    pub(crate) fn parse_unvalidated(s: &str) -> Result<Self, Error> {
+        let mut lines = s.lines();
+        let name_line = lines
+            .next()
+            .ok_or_else(|| Error::Parse("no name line".into()))?;
+        let name = name_line
+            .trim_end()
+            .strip_suffix(" map:")
+            .ok_or_else(|| Error::Parse(format!("not a map line: {name_line}")))?;
+
+        let entries = lines
+            .filter(|line| !line.is_empty())
+            .map(MapEntry::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| Error::Parse(format!("in {name}: {err}")))?;
+
+        Ok(Self::new_unvalidated(name, entries))
+    }
+
+    /// Find the entry which applies to this value.
+    ///
+    /// `None` when the identity mapping applies.
+    pub fn find_entry(&self, value: i64) -> Option<&MapEntry> {
         // linear scan might seem like an odd choice here, but I think it's justified:
         // there are only ~40 entries for any particular map in the input, and that will be "fast enough".
         // a more complicated data structure seems likely to introduce overhead which might overwhelm the
         // theoretical speed advantages, and is very likely to introduce opportunities for bugs to slip in
-        for entry in &self.entries {
-            if entry.contains(value) {
-                return entry.apply(value);
-            }
+        self.entries.iter().find(|entry| entry.contains(value))
+    }
+
+    pub fn apply(&self, value: i64) -> Result<i64, Error> {
+        match self.find_entry(value) {
+            Some(entry) => entry.apply(value).ok_or_else(|| self.overflow(entry)),
+            None => Ok(value),
         }
-        value
+    }
+
+    /// Map a segment which lies entirely within `entry`'s source range.
+    fn apply_segment(&self, entry: &MapEntry, input: SeedRange) -> Result<Segment, Error> {
+        entry
+            .apply(input.start)
+            .map(|start| SeedRange {
+                start,
+                length: input.length,
+            })
+            .filter(|output| output.end().is_some())
+            .map(|output| Segment {
+                input,
+                entry: Some(*entry),
+                output,
+            })
+            .ok_or_else(|| self.overflow(entry))
     }
 
     /// Apply this map to a seed range.
@@ -73,7 +171,15 @@ impl Map {
     /// more than one output range even if certain output ranges are contiguous.
     ///
     /// This function will never produce an empty output vector.
-    pub fn apply_range(&self, mut range: SeedRange) -> Vec<SeedRange> {
+    pub fn apply_range(&self, range: SeedRange) -> Result<Vec<SeedRange>, Error> {
+        let segments = self.split_range(range)?;
+        Ok(segments.into_iter().map(|segment| segment.output).collect())
+    }
+
+    /// Split a seed range into the segments which [`Map::apply_range`] maps independently.
+    ///
+    /// Each segment records the entry which mapped it, if any.
+    pub fn split_range(&self, mut range: SeedRange) -> Result<Vec<Segment>, Error> {
         let _original_length = range.length;
 
         let mut out = Vec::new();
@@ -82,22 +188,22 @@ impl Map {
         // implementation note: I'm doing manual bounds checking and unwrapping previously-checked
         // bounds quite a lot in here. given more time, I might be able to figure out a more elegant approach,
         // but this is what we've got for now.
+        //
+        // all comparisons against the end of the range are phrased in terms of `range.contains`,
+        // so that we never need to compute a possibly-overflowing endpoint.
 
         let mut eidx = 0;
-        loop {
-            // break if we're out of bounds
-            let Some(entry) = self.entries.get(eidx) else {
-                break;
-            };
+        while let Some(entry) = self.entries.get(eidx) {
+            let source_end = entry.source_end().ok_or_else(|| self.overflow(entry))?;
 
             // fast-forward to the first interesting point
-            if entry.source_end() <= range.start {
+            if source_end <= range.start {
                 eidx += 1;
                 continue;
             }
 
             // sanity: have we overshot our boundaries entirely?
-            if entry.source_start >= range.end() {
+            if entry.source_start >= range.start && !range.contains(entry.source_start) {
                 break;
             }
 
@@ -105,20 +211,20 @@ impl Map {
                 // we need an unmodified segment before the entry,
                 // and we know we're in bounds
                 (segment, range) = range.split_at(entry.source_start).unwrap();
-                out.push(segment);
+                out.push(Segment::identity(segment));
             }
 
             debug_assert!(entry.source_start <= range.start);
 
-            if entry.source_end() < range.end() {
+            if range.contains(source_end) {
                 // we need to split again, to snip out the mapped segment
-                (segment, range) = range.split_at(entry.source_end()).unwrap();
-                out.push(segment + entry.delta());
+                (segment, range) = range.split_at(source_end).unwrap();
+                out.push(self.apply_segment(entry, segment)?);
                 eidx += 1;
             } else {
                 // we can push the remainder of the range now, then break;
                 // we're done with our range
-                out.push(range + entry.delta());
+                out.push(self.apply_segment(entry, range)?);
                 // ensure we don't re-add the range again
                 range.length = 0;
                 break;
@@ -127,15 +233,15 @@ impl Map {
 
         if range.length > 0 {
             // most likely cause: all map entries were below the low end of the seed range
-            out.push(range);
+            out.push(Segment::identity(range));
         }
 
         debug_assert_eq!(
-            out.iter().map(|range| range.length).sum::<i64>(),
+            out.iter().map(|segment| segment.input.length).sum::<i64>(),
             _original_length
         );
 
-        out
+        Ok(out)
     }
 }
 
@@ -143,30 +249,47 @@ impl FromStr for Map {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let name_line = lines
-            .next()
-            .ok_or_else(|| Error::Parse("no name line".into()))?;
-        let name = name_line
-            .trim_end()
-            .strip_suffix(" map:")
-            .ok_or_else(|| Error::Parse(format!("not a map line: {name_line}")))?;
-
-        let entries = lines
-            .filter(|line| !line.is_empty())
-            .map(MapEntry::from_str)
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|err| Error::Parse(format!("in {name}: {err}")))?;
-
+        let Self { name, entries } = Self::parse_unvalidated(s)?;
         Self::new(name, entries)
     }
 }
 
+/// Writes the map in the puzzle's format, with entries in normalized (sorted) order.
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} map:", self.name)?;
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::strategies::{map, seed_range};
+    use proptest::prelude::*;
     use rstest::rstest;
 
+    proptest! {
+        #[test]
+        fn apply_range_agrees_with_apply(map in map(), range in seed_range()) {
+            let mut by_range = map
+                .apply_range(range)
+                .unwrap()
+                .into_iter()
+                .flat_map(|range| range.start..range.end().unwrap())
+                .collect::<Vec<_>>();
+            let mut pointwise = (range.start..range.end().unwrap())
+                .map(|value| map.apply(value).unwrap())
+                .collect::<Vec<_>>();
+            by_range.sort_unstable();
+            pointwise.sort_unstable();
+            prop_assert_eq!(by_range, pointwise);
+        }
+    }
+
     #[rstest]
     #[case(0, 0)]
     #[case(49, 49)]
@@ -194,7 +317,7 @@ mod tests {
         )
         .unwrap();
 
-        let got = map.apply(value);
+        let got = map.apply(value).unwrap();
         assert_eq!(got, expect);
     }
 
@@ -214,7 +337,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(map.apply_range(range), vec![range]);
+        assert_eq!(map.apply_range(range).unwrap(), vec![range]);
     }
 
     #[test]
@@ -233,7 +356,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(map.apply_range(range), vec![range]);
+        assert_eq!(map.apply_range(range).unwrap(), vec![range]);
     }
 
     #[test]
@@ -257,7 +380,7 @@ mod tests {
             length: 1,
         }];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -281,7 +404,7 @@ mod tests {
             length: 1,
         }];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -305,7 +428,7 @@ mod tests {
             length: 1,
         }];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -329,7 +452,7 @@ mod tests {
             length: 1,
         }];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -363,7 +486,7 @@ mod tests {
             },
         ];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -393,7 +516,7 @@ mod tests {
             },
         ];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -423,7 +546,7 @@ mod tests {
             },
         ];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 
     #[test]
@@ -495,6 +618,72 @@ mod tests {
             },
         ];
 
-        assert_eq!(map.apply_range(range), expect);
+        assert_eq!(map.apply_range(range).unwrap(), expect);
+    }
+
+    #[test]
+    fn split_range_records_entries() {
+        let entry = MapEntry {
+            destination_start: 10,
+            source_start: 3,
+            range_length: 2,
+        };
+        let map = Map::new("split", [entry]).unwrap();
+        let range = SeedRange {
+            start: 2,
+            length: 4,
+        };
+
+        let entries = map
+            .split_range(range)
+            .unwrap()
+            .into_iter()
+            .map(|segment| segment.entry)
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![None, Some(entry), None]);
+    }
+
+    #[test]
+    fn overflowing_entry_is_rejected() {
+        let err = Map::new(
+            "overflow",
+            [MapEntry {
+                destination_start: i64::MAX - 1,
+                source_start: 0,
+                range_length: 2,
+            }],
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Overflow { name, .. } if name == "overflow"));
+    }
+
+    #[test]
+    fn apply_range_near_max() {
+        let range = SeedRange {
+            start: i64::MAX - 4,
+            length: 3,
+        };
+        let map = Map::new(
+            "near-max",
+            [MapEntry {
+                destination_start: i64::MAX - 3,
+                source_start: i64::MAX - 5,
+                range_length: 2,
+            }],
+        )
+        .unwrap();
+
+        let expect = vec![
+            SeedRange {
+                start: i64::MAX - 2,
+                length: 1,
+            },
+            SeedRange {
+                start: i64::MAX - 3,
+                length: 2,
+            },
+        ];
+
+        assert_eq!(map.apply_range(range).unwrap(), expect);
     }
 }
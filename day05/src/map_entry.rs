@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, parse_display::FromStr, parse_display::Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
 #[display("{destination_start} {source_start} {range_length}")]
 pub struct MapEntry {
     pub(crate) destination_start: i64,
@@ -7,29 +7,31 @@ pub struct MapEntry {
 }
 
 impl MapEntry {
+    /// `None` if the end of the destination range is not representable.
     #[inline]
-    #[allow(dead_code)]
-    pub(crate) fn destination_end(&self) -> i64 {
-        self.destination_start + self.range_length
+    pub(crate) fn destination_end(&self) -> Option<i64> {
+        self.destination_start.checked_add(self.range_length)
     }
+    /// `None` if the end of the source range is not representable.
     #[inline]
-    pub(crate) fn source_end(&self) -> i64 {
-        self.source_start + self.range_length
+    pub(crate) fn source_end(&self) -> Option<i64> {
+        self.source_start.checked_add(self.range_length)
     }
     #[inline]
     pub(crate) fn contains(&self, v: i64) -> bool {
-        (self.source_start..self.source_end()).contains(&v)
+        // phrased in terms of the offset so that this is correct even when `source_end` overflows
+        v.checked_sub(self.source_start)
+            .is_some_and(|offset| (0..self.range_length).contains(&offset))
     }
+    /// `None` if the mapped value is not representable.
     #[inline]
-    pub(crate) fn delta(&self) -> i64 {
-        self.destination_start - self.source_start
-    }
-    #[inline]
-    pub fn apply(&self, value: i64) -> i64 {
+    pub fn apply(&self, value: i64) -> Option<i64> {
         if self.contains(value) {
-            value + self.delta()
+            // `contains` guarantees that this subtraction is in `0..range_length`
+            self.destination_start
+                .checked_add(value - self.source_start)
         } else {
-            value
+            Some(value)
         }
     }
 }
@@ -54,7 +56,33 @@ mod tests {
             source_start: 50,
             range_length: 48,
         };
-        let got = entry.apply(value);
+        let got = entry.apply(value).unwrap();
         assert_eq!(got, expect);
     }
+
+    #[test]
+    fn extreme_entry() {
+        let entry = MapEntry {
+            destination_start: i64::MAX - 1,
+            source_start: i64::MIN,
+            range_length: 2,
+        };
+        assert_eq!(entry.source_end(), Some(i64::MIN + 2));
+        assert_eq!(entry.destination_end(), None);
+        assert!(entry.contains(i64::MIN + 1));
+        assert!(!entry.contains(i64::MAX));
+        assert_eq!(entry.apply(i64::MIN + 1), Some(i64::MAX));
+        assert_eq!(entry.apply(i64::MAX), Some(i64::MAX));
+    }
+
+    #[test]
+    fn overflowing_apply() {
+        let entry = MapEntry {
+            destination_start: i64::MAX,
+            source_start: 0,
+            range_length: 10,
+        };
+        assert_eq!(entry.apply(0), Some(i64::MAX));
+        assert_eq!(entry.apply(1), None);
+    }
 }
@@ -1,7 +1,4 @@
-use std::{
-    ops::{Add, AddAssign},
-    str::FromStr,
-};
+use std::{fmt, str::FromStr};
 
 use crate::Error;
 
@@ -12,13 +9,17 @@ pub struct SeedRange {
 }
 
 impl SeedRange {
+    /// `None` if the end of this range is not representable.
     #[inline]
-    pub fn end(&self) -> i64 {
-        self.start + self.length
+    pub fn end(&self) -> Option<i64> {
+        self.start.checked_add(self.length)
     }
 
     pub fn contains(&self, value: i64) -> bool {
-        (self.start..self.end()).contains(&value)
+        // phrased in terms of the offset so that this is correct even when `end` overflows
+        value
+            .checked_sub(self.start)
+            .is_some_and(|offset| (0..self.length).contains(&offset))
     }
 
     /// Split this seed range at a particular value.
@@ -31,34 +32,22 @@ impl SeedRange {
     pub fn split_at(self, split_point: i64) -> Option<(Self, Self)> {
         self.contains(split_point).then(|| {
             let Self { start, length } = self;
+            // `contains` guarantees that this is in `0..length`
+            let first_length = split_point - start;
             let first = Self {
                 start,
-                length: split_point - start,
+                length: first_length,
             };
             let second = Self {
                 start: split_point,
-                length: start + length - split_point,
+                length: length - first_length,
             };
             (first, second)
         })
     }
 }
 
-impl AddAssign<i64> for SeedRange {
-    fn add_assign(&mut self, rhs: i64) {
-        self.start += rhs;
-    }
-}
-
-impl Add<i64> for SeedRange {
-    type Output = Self;
-
-    fn add(mut self, rhs: i64) -> Self::Output {
-        self += rhs;
-        self
-    }
-}
-
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SeedRanges(pub Vec<SeedRange>);
 
 impl FromStr for SeedRanges {
@@ -80,13 +69,46 @@ impl FromStr for SeedRanges {
             let [start, length] = TryInto::<[_; 2]>::try_into(chunk)
                 .map_err(|_err| Error::Parse("wrong seed range chunk size".into()))?;
 
-            ranges.push(SeedRange { start, length });
+            if length <= 0 {
+                return Err(Error::Parse(format!(
+                    "seed range {start} {length} contains no seeds"
+                )));
+            }
+            let range = SeedRange { start, length };
+            if range.end().is_none() {
+                return Err(Error::Parse(format!(
+                    "seed range {start} {length} overflows"
+                )));
+            }
+            ranges.push(range);
         }
 
         Ok(Self(ranges))
     }
 }
 
+/// Displays as a half-open range of seed values, i.e. `79..93`.
+impl fmt::Display for SeedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // widen so that displaying can never overflow
+        let end = self.start as i128 + self.length as i128;
+        write!(f, "{}..{end}", self.start)
+    }
+}
+
+impl fmt::Display for SeedRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds: ")?;
+        for (idx, SeedRange { start, length }) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{start} {length}")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,4 +135,26 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn seed_range_overflow() {
+        let range = SeedRange {
+            start: i64::MAX - 1,
+            length: 2,
+        };
+        assert_eq!(range.end(), None);
+        assert!(range.contains(i64::MAX));
+        assert!(!range.contains(i64::MIN));
+        assert!("seeds: 9223372036854775806 2"
+            .parse::<SeedRanges>()
+            .is_err());
+        assert!("seeds: 9223372036854775806 1".parse::<SeedRanges>().is_ok());
+    }
+
+    #[test]
+    fn empty_seed_range() {
+        assert!("seeds: 79 0".parse::<SeedRanges>().is_err());
+        assert!("seeds: 79 14 55 -3".parse::<SeedRanges>().is_err());
+        assert!("seeds: 79 14 55 13".parse::<SeedRanges>().is_ok());
+    }
 }
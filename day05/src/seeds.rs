@@ -1,7 +1,8 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use crate::Error;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Seeds(pub Vec<i64>);
 
 impl FromStr for Seeds {
@@ -21,3 +22,16 @@ impl FromStr for Seeds {
         Ok(Self(items))
     }
 }
+
+impl fmt::Display for Seeds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds: ")?;
+        for (idx, seed) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{seed}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+//! Proptest strategies shared between test modules.
+
+use proptest::prelude::*;
+
+use crate::{map::Map, map_entry::MapEntry, seed_ranges::SeedRange};
+
+/// Non-overlapping entries, so that the resulting map always validates.
+///
+/// Sources start near 0 and are packed fairly densely, so that small seed ranges
+/// are likely to interact with several entries and gaps.
+pub(crate) fn entries() -> impl Strategy<Value = Vec<MapEntry>> {
+    prop::collection::vec((0_i64..100, 1_i64..100, 0_i64..1_000_000), 1..20).prop_map(|specs| {
+        let mut source_start = 0;
+        specs
+            .into_iter()
+            .map(|(gap, range_length, destination_start)| {
+                source_start += gap;
+                let entry = MapEntry {
+                    destination_start,
+                    source_start,
+                    range_length,
+                };
+                source_start += range_length;
+                entry
+            })
+            .collect()
+    })
+}
+
+pub(crate) fn map() -> impl Strategy<Value = Map> {
+    ("[a-z]{1,10}-to-[a-z]{1,10}", entries())
+        .prop_map(|(name, entries)| Map::new(name, entries).expect("entries do not overlap"))
+}
+
+/// Small seed ranges in the same neighborhood as the sources generated by [`entries`].
+pub(crate) fn seed_range() -> impl Strategy<Value = SeedRange> {
+    (0_i64..3_000, 1_i64..300).prop_map(|(start, length)| SeedRange { start, length })
+}
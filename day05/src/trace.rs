@@ -0,0 +1,108 @@
+use std::{path::Path, str::FromStr};
+
+use crate::{almanac::Almanac, seed_ranges::SeedRange, seeds::Seeds, Error};
+
+/// What to trace through the almanac: either a single seed, or a half-open range of seeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceTarget {
+    Seed(i64),
+    Range(SeedRange),
+}
+
+impl FromStr for TraceTarget {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_value = |token: &str| {
+            token
+                .trim()
+                .parse::<i64>()
+                .map_err(|err| Error::Parse(format!("trace target \"{token}\": {err}")))
+        };
+
+        let Some((start, end)) = s.split_once("..") else {
+            return parse_value(s).map(Self::Seed);
+        };
+        let start = parse_value(start)?;
+        let end = parse_value(end)?;
+        let length = end
+            .checked_sub(start)
+            .filter(|&length| length > 0)
+            .ok_or_else(|| Error::Parse(format!("trace target \"{s}\" is an empty range")))?;
+        Ok(Self::Range(SeedRange { start, length }))
+    }
+}
+
+fn trace_seed(almanac: &Almanac<Seeds>, mut value: i64) -> Result<(), Error> {
+    println!("seed {value}");
+    for map in &almanac.maps {
+        let next = map.apply(value)?;
+        match map.find_entry(value) {
+            Some(entry) => println!("  {}: {value} -> {next} via [{entry}]", map.name()),
+            None => println!("  {}: {value} -> {next} (identity)", map.name()),
+        }
+        value = next;
+    }
+    println!("location {value}");
+    Ok(())
+}
+
+fn trace_range(almanac: &Almanac<Seeds>, range: SeedRange) -> Result<(), Error> {
+    println!("seeds {range}");
+    let mut ranges = vec![range];
+    for map in &almanac.maps {
+        println!("  {}:", map.name());
+        let mut next_ranges = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            let segments = map.split_range(range)?;
+            if segments.len() > 1 {
+                println!("    {range} split into {} segments", segments.len());
+            }
+            for segment in segments {
+                match segment.entry {
+                    Some(entry) => println!(
+                        "      {} -> {} via [{entry}]",
+                        segment.input, segment.output
+                    ),
+                    None => println!("      {} -> {} (identity)", segment.input, segment.output),
+                }
+                next_ranges.push(segment.output);
+            }
+        }
+        ranges = next_ranges;
+    }
+
+    let lowest_location = ranges
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or(Error::NoSolution)?;
+    println!("lowest location {lowest_location}");
+    Ok(())
+}
+
+/// Print the value or range after each stage of the almanac, along with the entry which mapped it.
+pub fn trace(input: &Path, target: TraceTarget) -> Result<(), Error> {
+    let almanac = Almanac::<Seeds>::load(input)?;
+    match target {
+        TraceTarget::Seed(seed) => trace_seed(&almanac, seed),
+        TraceTarget::Range(range) => trace_range(&almanac, range),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("79", Some(TraceTarget::Seed(79)))]
+    #[case("79..93", Some(TraceTarget::Range(SeedRange { start: 79, length: 14 })))]
+    #[case("-5..5", Some(TraceTarget::Range(SeedRange { start: -5, length: 10 })))]
+    #[case("93..79", None)]
+    #[case("79..79", None)]
+    #[case("seventy-nine", None)]
+    fn parse_trace_target(#[case] s: &str, #[case] expect: Option<TraceTarget>) {
+        assert_eq!(s.parse::<TraceTarget>().ok(), expect);
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{map::Map, seed_ranges::SeedRange, Error};
+
+/// Seeds are handed out to worker threads in chunks of this size.
+const CHUNK_SIZE: i64 = 1 << 16;
+
+fn chunks(mut range: SeedRange) -> impl Iterator<Item = SeedRange> {
+    std::iter::from_fn(move || {
+        if range.length <= 0 {
+            return None;
+        }
+        let split = range
+            .start
+            .checked_add(CHUNK_SIZE)
+            .and_then(|split_point| range.split_at(split_point));
+        let chunk = match split {
+            Some((chunk, rest)) => {
+                range = rest;
+                chunk
+            }
+            None => {
+                // the remainder fits in a single chunk
+                let chunk = range;
+                range.length = 0;
+                chunk
+            }
+        };
+        Some(chunk)
+    })
+}
+
+fn location_of(maps: &[Map], mut value: i64) -> Result<i64, Error> {
+    for map in maps {
+        value = map.apply(value)?;
+    }
+    Ok(value)
+}
+
+/// Find the lowest location reachable from any seed in `ranges`, by mapping each seed individually.
+///
+/// This uses only the pointwise [`Map::apply`], so it is independent of the range-splitting logic.
+pub fn lowest_location_brute_force(maps: &[Map], ranges: &[SeedRange]) -> Result<i64, Error> {
+    let chunks = ranges.iter().copied().flat_map(chunks).collect::<Vec<_>>();
+    let next_chunk = AtomicUsize::new(0);
+    let n_workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    // set by the first worker to fail, so that the others can give up rather than finish their work in vain
+    let failed = AtomicBool::new(false);
+
+    let worker = || -> Result<Option<i64>, Error> {
+        let mut lowest = None;
+        while let Some(chunk) = chunks.get(next_chunk.fetch_add(1, Ordering::Relaxed)) {
+            if failed.load(Ordering::Relaxed) {
+                break;
+            }
+            for offset in 0..chunk.length {
+                let location = location_of(maps, chunk.start + offset).inspect_err(|_err| {
+                    failed.store(true, Ordering::Relaxed);
+                })?;
+                lowest = Some(lowest.map_or(location, |lowest: i64| lowest.min(location)));
+            }
+        }
+        Ok(lowest)
+    };
+
+    let lowests = std::thread::scope(|scope| {
+        let handles = (0..n_workers)
+            .map(|_| scope.spawn(worker))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("worker threads do not panic"))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    lowests.into_iter().flatten().min().ok_or(Error::NoSolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lowest_location_by_ranges,
+        strategies::{map, seed_range},
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn chunks_cover_range() {
+        let range = SeedRange {
+            start: 5,
+            length: 2 * CHUNK_SIZE + 3,
+        };
+        let chunks = chunks(range).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].start, 5);
+        assert_eq!(chunks[2].length, 3);
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.length).sum::<i64>(),
+            range.length
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn brute_force_agrees_with_ranges(
+            maps in prop::collection::vec(map(), 1..8),
+            ranges in prop::collection::vec(seed_range(), 1..5),
+        ) {
+            let by_ranges = lowest_location_by_ranges(&maps, ranges.clone()).unwrap();
+            let brute_force = lowest_location_brute_force(&maps, &ranges).unwrap();
+            prop_assert_eq!(by_ranges, brute_force);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use counter::Counter;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use day07::{
+    card::Card,
+    hand::{Hand, HandType},
+    ruleset::Ruleset,
+    table::HandTable,
+};
+
+const N_HANDS: usize = 1_000_000;
+
+/// A tiny linear congruential generator, so that the benchmark input is deterministic.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize
+    }
+}
+
+fn generate() -> Vec<Vec<Card>> {
+    let mut rng = Lcg(0x5eed);
+    (0..N_HANDS)
+        .map(|_| {
+            (0..5)
+                .map(|_| Card::ALL[rng.next() % Card::COUNT])
+                .collect()
+        })
+        .collect()
+}
+
+/// The hand types of the original part 2 solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum CounterType {
+    FiveOfAKind,
+    FourOfAKind,
+    FullHouse,
+    ThreeOfAKind,
+    TwoPair,
+    OnePair,
+    HighCard,
+}
+
+/// The original part 2 classification: count the cards, and add the jokers to the most common.
+fn classify_with_counter(cards: &[Card]) -> CounterType {
+    let mut counter = cards.iter().copied().collect::<Counter<_>>();
+    let joker_count = counter.remove(&Card::Jack).unwrap_or_default();
+    let mut frequencies = counter.most_common_ordered();
+    if let Some((_, count)) = frequencies.get_mut(0) {
+        *count += joker_count;
+    } else {
+        frequencies.push((Card::Jack, joker_count));
+    }
+    match frequencies.as_slice() {
+        [(_, 5)] => CounterType::FiveOfAKind,
+        [(_, 4), ..] => CounterType::FourOfAKind,
+        [(_, 3), (_, 2)] => CounterType::FullHouse,
+        [(_, 3), ..] => CounterType::ThreeOfAKind,
+        [(_, 2), (_, 2), ..] => CounterType::TwoPair,
+        [(_, 2), ..] => CounterType::OnePair,
+        _ => CounterType::HighCard,
+    }
+}
+
+fn bench_classify(c: &mut Criterion) {
+    let hands = generate();
+    let ruleset = Ruleset::part2();
+    let table = HandTable::new(&ruleset).unwrap();
+
+    let mut group = c.benchmark_group("classify pt 2");
+    group.sample_size(10);
+    group.bench_function("counter", |b| {
+        b.iter(|| {
+            hands
+                .iter()
+                .map(|cards| classify_with_counter(black_box(cards)))
+                .filter(|&hand_type| hand_type == CounterType::FullHouse)
+                .count()
+        })
+    });
+    group.bench_function("wild assignment", |b| {
+        let full_house = HandType::groups(&[3, 2]);
+        b.iter(|| {
+            hands
+                .iter()
+                .map(|cards| HandType::classify(black_box(cards), None, &ruleset))
+                .filter(|hand_type| *hand_type == full_house)
+                .count()
+        })
+    });
+    group.bench_function("lookup", |b| {
+        let full_house = HandType::groups(&[3, 2]);
+        b.iter(|| {
+            hands
+                .iter()
+                .map(|cards| table.hand_type(black_box(cards), None).unwrap())
+                .filter(|&hand_type| *hand_type == full_house)
+                .count()
+        })
+    });
+    group.bench_function("build lookup table", |b| {
+        b.iter(|| HandTable::new(black_box(&ruleset)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_sort(c: &mut Criterion) {
+    let cards = generate();
+    let ruleset = Ruleset::part2();
+    let table = HandTable::new(&ruleset).unwrap();
+    let hands = cards
+        .iter()
+        .map(|cards| Hand::new(cards.clone(), None, &ruleset).unwrap())
+        .collect::<Vec<_>>();
+    let keys = cards
+        .iter()
+        .map(|cards| table.sort_key(cards, None).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("sort pt 2");
+    group.sample_size(10);
+    group.bench_function("hand sort keys", |b| {
+        b.iter_batched(
+            || hands.clone(),
+            |mut hands| hands.sort_by(|left, right| left.sort_key().cmp(&right.sort_key())),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("packed sort keys", |b| {
+        b.iter_batched(
+            || keys.clone(),
+            |mut keys| keys.sort_unstable(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_classify, bench_sort);
+criterion_main!(benches);
@@ -0,0 +1,131 @@
+/// A card's face, independent of any ruleset.
+///
+/// The ruleset decides how strong each card is and whether it is wild,
+/// so the declaration order here carries no meaning.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+pub enum Card {
+    #[display("2")]
+    Two,
+    #[display("3")]
+    Three,
+    #[display("4")]
+    Four,
+    #[display("5")]
+    Five,
+    #[display("6")]
+    Six,
+    #[display("7")]
+    Seven,
+    #[display("8")]
+    Eight,
+    #[display("9")]
+    Nine,
+    #[display("T")]
+    Ten,
+    #[display("J")]
+    Jack,
+    #[display("Q")]
+    Queen,
+    #[display("K")]
+    King,
+    #[display("A")]
+    Ace,
+}
+
+impl Card {
+    pub const COUNT: usize = 13;
+
+    pub const ALL: [Card; Card::COUNT] = [
+        Card::Two,
+        Card::Three,
+        Card::Four,
+        Card::Five,
+        Card::Six,
+        Card::Seven,
+        Card::Eight,
+        Card::Nine,
+        Card::Ten,
+        Card::Jack,
+        Card::Queen,
+        Card::King,
+        Card::Ace,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A card's suit.
+///
+/// Puzzle inputs have no suits; they are only needed for flushes.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+pub enum Suit {
+    #[display("c")]
+    Clubs,
+    #[display("d")]
+    Diamonds,
+    #[display("h")]
+    Hearts,
+    #[display("s")]
+    Spades,
+}
+
+/// A set of cards, as a bitmask over [`Card::index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct CardSet(u16);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    pub const ALL: CardSet = CardSet((1 << Card::COUNT) - 1);
+
+    pub fn insert(&mut self, card: Card) {
+        self.0 |= 1 << card.index();
+    }
+
+    pub fn contains(self, card: Card) -> bool {
+        self.0 & (1 << card.index()) != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Card> {
+        Card::ALL
+            .into_iter()
+            .filter(move |&card| self.contains(card))
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut set = CardSet::EMPTY;
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
@@ -0,0 +1,69 @@
+use std::{fmt::Write, path::Path};
+
+use aoclib::parse;
+
+use crate::{
+    card::Card,
+    hand::{Decider, Hand},
+    rank_hands,
+    ruleset::Ruleset,
+    CardsWithBid, Error,
+};
+
+fn format_cards(cards: &[Card]) -> String {
+    cards.iter().fold(String::new(), |mut out, card| {
+        let _ = write!(out, "{card}");
+        out
+    })
+}
+
+/// Describe how `hand` came to rank directly above `previous`.
+fn describe_decider(hand: &Hand, previous: &Hand) -> String {
+    match previous.decider(hand) {
+        Decider::Type => "type".into(),
+        Decider::Position(position) => format!(
+            "card {}: {} over {}",
+            position + 1,
+            hand.cards[position],
+            previous.cards[position]
+        ),
+        Decider::Tie => "tie".into(),
+    }
+}
+
+/// Print every hand in rank order under the ruleset: its type, what its wild cards stood in for,
+/// its bid and winnings, and what ranked it above the hand before it.
+pub fn explain(input: &Path, ruleset: &Ruleset, label: &str) -> Result<(), Error> {
+    let hand_bids = parse::<CardsWithBid>(input)?.collect::<Vec<_>>();
+    let hands = rank_hands(&hand_bids, ruleset)?;
+    let width = ruleset.hand_size().max("hand".len());
+
+    println!("rankings ({label}):");
+    println!(
+        "  {:>5}  {:<width$}  {:<16}  {:<width$}  {:>6}  {:>9}  above previous",
+        "rank", "hand", "type", "as", "bid", "winnings"
+    );
+    let mut total_winnings = 0;
+    for (idx, (hand, bid)) in hands.iter().enumerate() {
+        let rank = idx as u64 + 1;
+        let winnings = rank * bid;
+        total_winnings += winnings;
+
+        let assigned = if hand.assignments.is_empty() {
+            "-".into()
+        } else {
+            format_cards(&hand.assigned_cards())
+        };
+        let decider = match idx.checked_sub(1) {
+            Some(previous) => describe_decider(hand, &hands[previous].0),
+            None => "-".into(),
+        };
+        println!(
+            "  {rank:>5}  {:<width$}  {:<16}  {assigned:<width$}  {bid:>6}  {winnings:>9}  {decider}",
+            format_cards(&hand.cards),
+            hand.type_.to_string(),
+        );
+    }
+    println!("total winnings ({label}): {total_winnings}");
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! Helpers shared between test modules.
+
+use crate::card::{Card, Suit};
+
+/// Parse one card per character, e.g. `KTJJT`.
+pub(crate) fn cards(cards: &str) -> Vec<Card> {
+    cards
+        .chars()
+        .map(|c| c.to_string().parse::<Card>().unwrap())
+        .collect()
+}
+
+/// Parse one suit per character, e.g. `chsdc`.
+pub(crate) fn suits(suits: &str) -> Vec<Suit> {
+    suits
+        .chars()
+        .map(|c| c.to_string().parse::<Suit>().unwrap())
+        .collect()
+}
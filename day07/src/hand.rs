@@ -0,0 +1,465 @@
+use std::{cmp::Reverse, fmt};
+
+use crate::{
+    card::{Card, Suit},
+    ruleset::{RankKey, Ruleset},
+    Error,
+};
+
+/// Categories which don't follow from how the faces group together, and which rulesets may enable.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Special {
+    /// Every face is distinct, and their strengths are consecutive.
+    Straight,
+    /// Every card has the same suit.
+    Flush,
+    /// Both a straight and a flush.
+    StraightFlush,
+}
+
+/// A hand's category.
+///
+/// How categories rank against each other is up to the [`Ruleset`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HandType {
+    /// Cards grouped by matching faces: the size of each group, largest first.
+    ///
+    /// For five cards, `[3, 2]` is a full house and `[2, 2, 1]` is two pair.
+    Groups(Vec<u8>),
+    Special(Special),
+}
+
+impl HandType {
+    pub fn groups(pattern: &[u8]) -> Self {
+        Self::Groups(pattern.to_vec())
+    }
+
+    /// Classify a hand from how many of each card it holds.
+    fn from_counts(counts: &[u8]) -> Self {
+        let mut frequencies = counts
+            .iter()
+            .copied()
+            .filter(|&count| count > 0)
+            .collect::<Vec<_>>();
+        frequencies.sort_unstable_by_key(|&count| Reverse(count));
+        Self::Groups(frequencies)
+    }
+
+    /// Classify a hand under the ruleset, assigning each wild card so as to make the strongest hand.
+    ///
+    /// `suits`, when known, are in the same order as `cards`.
+    pub fn classify(cards: &[Card], suits: Option<&[Suit]>, ruleset: &Ruleset) -> Self {
+        Self::classify_assigned(cards, suits, ruleset).0
+    }
+
+    /// As [`Self::classify`], but also report which card each wild card stood in for.
+    ///
+    /// Assignments are `(position, card)` pairs, in order of position.
+    fn classify_assigned(
+        cards: &[Card],
+        suits: Option<&[Suit]>,
+        ruleset: &Ruleset,
+    ) -> (Self, Vec<(usize, Card)>) {
+        let mut counts = [0; Card::COUNT];
+        let mut wilds = Vec::new();
+        for (position, &card) in cards.iter().enumerate() {
+            if ruleset.is_wild(card) {
+                wilds.push((card, position));
+            } else {
+                counts[card.index()] += 1;
+            }
+        }
+
+        let is_flush = is_flush(cards, suits, ruleset);
+
+        // identical wild cards are interchangeable, so we only need to consider one ordering of their assignments
+        wilds.sort_unstable();
+        let wild_cards = wilds.iter().map(|&(card, _)| card).collect::<Vec<_>>();
+        let mut targets = Vec::with_capacity(wilds.len());
+        let mut best = None;
+        best_assignment(
+            &mut counts,
+            &wild_cards,
+            0,
+            is_flush,
+            ruleset,
+            &mut targets,
+            &mut best,
+        );
+        let (_key, hand_type, targets) = best.expect("grouped categories always have a rank");
+
+        let mut assignments = wilds
+            .into_iter()
+            .zip(targets)
+            .map(|((_card, position), target)| (position, target))
+            .collect::<Vec<_>>();
+        assignments.sort_unstable();
+        (hand_type, assignments)
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandType::Groups(pattern) => match pattern.as_slice() {
+                [5] => write!(f, "five of a kind"),
+                [4, 1] => write!(f, "four of a kind"),
+                [3, 2] => write!(f, "full house"),
+                [3, 1, 1] => write!(f, "three of a kind"),
+                [2, 2, 1] => write!(f, "two pair"),
+                [2, 1, 1, 1] => write!(f, "one pair"),
+                [1, 1, 1, 1, 1] => write!(f, "high card"),
+                _ => write!(f, "{}", format_pattern(pattern)),
+            },
+            HandType::Special(Special::StraightFlush) => write!(f, "straight flush"),
+            HandType::Special(special) => write!(f, "{special}"),
+        }
+    }
+}
+
+/// Write a group pattern as in ruleset configs, e.g. `3+2`.
+pub(crate) fn format_pattern(pattern: &[u8]) -> String {
+    pattern
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Whether the hand's suits are known, and every card which is not wild has the same suit.
+///
+/// Wild cards take on whichever suit is needed.
+pub(crate) fn is_flush(cards: &[Card], suits: Option<&[Suit]>, ruleset: &Ruleset) -> bool {
+    suits.is_some_and(|suits| {
+        let mut natural_suits = cards
+            .iter()
+            .zip(suits)
+            .filter(|(&card, _suit)| !ruleset.is_wild(card))
+            .map(|(_card, suit)| suit);
+        let first = natural_suits.next();
+        natural_suits.all(|suit| Some(suit) == first)
+    })
+}
+
+fn is_straight(counts: &[u8; Card::COUNT], ruleset: &Ruleset) -> bool {
+    if counts.iter().any(|&count| count > 1) {
+        return false;
+    }
+    let (min, max, n) = Card::ALL
+        .into_iter()
+        .filter(|card| counts[card.index()] == 1)
+        .map(|card| ruleset.strength(card))
+        .fold((u8::MAX, 0, 0), |(min, max, n), strength| {
+            (min.min(strength), max.max(strength), n + 1)
+        });
+    // a lone card doesn't make a run
+    n >= 2 && max - min + 1 == n
+}
+
+/// Try every assignment of the `wilds` to cards they may stand in for, keeping the strongest resulting type in `best`,
+/// along with the card each wild stood in for.
+///
+/// `min_target` is the lowest card index which the first wild may be assigned, so that
+/// runs of identical wild cards are assigned in non-decreasing order. `targets` holds the
+/// assignments made so far.
+fn best_assignment(
+    counts: &mut [u8; Card::COUNT],
+    wilds: &[Card],
+    min_target: usize,
+    is_flush: bool,
+    ruleset: &Ruleset,
+    targets: &mut Vec<Card>,
+    best: &mut Option<(RankKey, HandType, Vec<Card>)>,
+) {
+    let Some((&wild, rest)) = wilds.split_first() else {
+        let is_straight = is_straight(counts, ruleset);
+        let candidates = [
+            Some(HandType::from_counts(counts)),
+            is_straight.then_some(HandType::Special(Special::Straight)),
+            is_flush.then_some(HandType::Special(Special::Flush)),
+            (is_straight && is_flush).then_some(HandType::Special(Special::StraightFlush)),
+        ];
+        for hand_type in candidates.into_iter().flatten() {
+            // categories the ruleset doesn't enable have no rank
+            let Some(key) = ruleset.rank_key(&hand_type) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|(best_key, ..)| key > *best_key) {
+                *best = Some((key, hand_type, targets.clone()));
+            }
+        }
+        return;
+    };
+
+    for target in ruleset
+        .stands_in_for(wild)
+        .iter()
+        .filter(|target| target.index() >= min_target)
+    {
+        let next_min_target = if rest.first() == Some(&wild) {
+            target.index()
+        } else {
+            0
+        };
+        counts[target.index()] += 1;
+        targets.push(target);
+        best_assignment(
+            counts,
+            rest,
+            next_min_target,
+            is_flush,
+            ruleset,
+            targets,
+            best,
+        );
+        targets.pop();
+        counts[target.index()] -= 1;
+    }
+}
+
+/// What decides the order of two hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decider {
+    /// Their types rank differently.
+    Type,
+    /// Their types rank the same, and this is the first position at which their cards differ in strength.
+    Position(usize),
+    /// Nothing: they are equally strong.
+    Tie,
+}
+
+/// A hand of cards, classified under some ruleset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub suits: Option<Vec<Suit>>,
+    pub type_: HandType,
+    /// For each wild card, its position in the hand and the card it stands in for.
+    pub assignments: Vec<(usize, Card)>,
+    rank: RankKey,
+    /// The ruleset's strength of each card, for breaking ties between hands of the same type.
+    strengths: Vec<u8>,
+}
+
+impl Hand {
+    pub fn new(
+        cards: Vec<Card>,
+        suits: Option<Vec<Suit>>,
+        ruleset: &Ruleset,
+    ) -> Result<Self, Error> {
+        if cards.len() != ruleset.hand_size() {
+            return Err(Error::Parse(format!(
+                "wrong length: expected {} cards but got {}",
+                ruleset.hand_size(),
+                cards.len()
+            )));
+        }
+        let (type_, assignments) = HandType::classify_assigned(&cards, suits.as_deref(), ruleset);
+        let rank = ruleset
+            .rank_key(&type_)
+            .expect("classification only produces ranked categories");
+        let strengths = cards.iter().map(|&card| ruleset.strength(card)).collect();
+        Ok(Hand {
+            cards,
+            suits,
+            type_,
+            assignments,
+            rank,
+            strengths,
+        })
+    }
+
+    /// Sorting by this key orders hands from weakest to strongest.
+    pub fn sort_key(&self) -> (&RankKey, &[u8]) {
+        (&self.rank, &self.strengths)
+    }
+
+    /// The cards the hand counts as, with each wild card replaced by what it stands in for.
+    pub fn assigned_cards(&self) -> Vec<Card> {
+        let mut cards = self.cards.clone();
+        for &(position, card) in &self.assignments {
+            cards[position] = card;
+        }
+        cards
+    }
+
+    /// What decides how this hand is ordered relative to `other`.
+    pub fn decider(&self, other: &Hand) -> Decider {
+        if self.rank != other.rank {
+            return Decider::Type;
+        }
+        self.strengths
+            .iter()
+            .zip(&other.strengths)
+            .position(|(left, right)| left != right)
+            .map_or(Decider::Tie, Decider::Position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{cards, suits};
+
+    fn classify(cards_: &str, ruleset: &Ruleset) -> HandType {
+        HandType::classify(&cards(cards_), None, ruleset)
+    }
+
+    #[test]
+    fn jokers() {
+        let ruleset = Ruleset::part2();
+        assert_eq!(classify("32T3K", &ruleset), HandType::groups(&[2, 1, 1, 1]));
+        assert_eq!(classify("T55J5", &ruleset), HandType::groups(&[4, 1]));
+        assert_eq!(classify("KTJJT", &ruleset), HandType::groups(&[4, 1]));
+        assert_eq!(classify("JJJJJ", &ruleset), HandType::groups(&[5]));
+        assert_eq!(classify("2345J", &ruleset), HandType::groups(&[2, 1, 1, 1]));
+        assert_eq!(classify("2233J", &ruleset), HandType::groups(&[3, 2]));
+    }
+
+    #[test]
+    fn assignments() {
+        let ruleset = Ruleset::part2();
+        let hand = Hand::new(cards("KTJJT"), None, &ruleset).unwrap();
+        assert_eq!(hand.assignments, vec![(2, Card::Ten), (3, Card::Ten)]);
+        assert_eq!(hand.assigned_cards(), cards("KTTTT"));
+        let hand = Hand::new(cards("32T3K"), None, &ruleset).unwrap();
+        assert!(hand.assignments.is_empty());
+    }
+
+    #[test]
+    fn deciders() {
+        let ruleset = Ruleset::part1();
+        let hand = |cards_: &str| Hand::new(cards(cards_), None, &ruleset).unwrap();
+        assert_eq!(hand("KK677").decider(&hand("KTJJT")), Decider::Position(1));
+        assert_eq!(hand("T55J5").decider(&hand("QQQJA")), Decider::Position(0));
+        assert_eq!(hand("32T3K").decider(&hand("KK677")), Decider::Type);
+        assert_eq!(hand("32T3K").decider(&hand("32T3K")), Decider::Tie);
+    }
+
+    #[test]
+    fn restricted_wilds() {
+        // twos may only become kings or queens
+        let ruleset = "order = 2 3 4 5 6 7 8 9 T J Q K A\nwild = 2: K Q"
+            .parse::<Ruleset>()
+            .unwrap();
+        // the two cannot become an ace
+        assert_eq!(classify("AAA23", &ruleset), HandType::groups(&[3, 1, 1]));
+        assert_eq!(classify("AA22K", &ruleset), HandType::groups(&[3, 2]));
+        assert_eq!(
+            classify("34562", &ruleset),
+            HandType::groups(&[1, 1, 1, 1, 1])
+        );
+        assert_eq!(classify("22345", &ruleset), HandType::groups(&[2, 1, 1, 1]));
+    }
+
+    #[test]
+    fn several_wild_ranks() {
+        let ruleset = "order = 2 3 4 5 6 7 8 9 T J Q K A\nwild = J\nwild = 2"
+            .parse::<Ruleset>()
+            .unwrap();
+        assert_eq!(classify("J2AKQ", &ruleset), HandType::groups(&[3, 1, 1]));
+        assert_eq!(classify("J2AAK", &ruleset), HandType::groups(&[4, 1]));
+    }
+
+    #[test]
+    fn other_hand_sizes() {
+        let ruleset = "order = 2 3 4 5 6 7 8 9 T J Q K A\nhand-size = 7"
+            .parse::<Ruleset>()
+            .unwrap();
+        assert_eq!(classify("AAAKKQQ", &ruleset), HandType::groups(&[3, 2, 2]));
+        assert_eq!(classify("AAAKKQQ", &ruleset).to_string(), "3+2+2");
+        assert!(Hand::new(cards("AAAKK"), None, &ruleset).is_err());
+        let weaker = Hand::new(cards("AAAKKQ2"), None, &ruleset).unwrap();
+        let stronger = Hand::new(cards("22223KQ"), None, &ruleset).unwrap();
+        assert!(weaker.sort_key() < stronger.sort_key());
+    }
+
+    #[test]
+    fn single_card_is_not_a_straight() {
+        let ruleset = "
+            order = 2 3 4 5 6 7 8 9 T J Q K A
+            hand-size = 1
+            straight = above 1
+        "
+        .parse::<Ruleset>()
+        .unwrap();
+        assert_eq!(classify("A", &ruleset), HandType::groups(&[1]));
+
+        let ruleset = "
+            order = 2 3 4 5 6 7 8 9 T J Q K A
+            hand-size = 2
+            straight = above 1+1
+        "
+        .parse::<Ruleset>()
+        .unwrap();
+        assert_eq!(
+            classify("AK", &ruleset),
+            HandType::Special(Special::Straight)
+        );
+    }
+
+    #[test]
+    fn straights_and_flushes() {
+        let ruleset = "
+            order = 2 3 4 5 6 7 8 9 T J Q K A
+            straight = above 3+1+1
+            flush = above 3+1+1
+            straight-flush = above 4+1
+        "
+        .parse::<Ruleset>()
+        .unwrap();
+        let hand = |cards_: &str, suits_: &str| {
+            Hand::new(cards(cards_), Some(suits(suits_)), &ruleset).unwrap()
+        };
+
+        let three = hand("AAA23", "chsdc");
+        let straight = hand("23456", "chchc");
+        let flush = hand("2345K", "hhhhh");
+        let full_house = hand("22333", "chsdc");
+        let straight_flush = hand("9TJQK", "sssss");
+
+        assert_eq!(straight.type_, HandType::Special(Special::Straight));
+        assert_eq!(flush.type_, HandType::Special(Special::Flush));
+        assert_eq!(
+            straight_flush.type_,
+            HandType::Special(Special::StraightFlush)
+        );
+        assert!(three.sort_key() < straight.sort_key());
+        assert!(straight.sort_key() < flush.sort_key());
+        assert!(flush.sort_key() < full_house.sort_key());
+        assert!(full_house.sort_key() < straight_flush.sort_key());
+    }
+
+    #[test]
+    fn wild_straights() {
+        let ruleset = "
+            order = J 2 3 4 5 6 7 8 9 T Q K A
+            wild = J
+            straight = above 3+1+1
+        "
+        .parse::<Ruleset>()
+        .unwrap();
+        assert_eq!(
+            classify("2J456", &ruleset),
+            HandType::Special(Special::Straight)
+        );
+        // three of a kind is available, but the straight ranks higher
+        assert_eq!(
+            classify("JJ456", &ruleset),
+            HandType::Special(Special::Straight)
+        );
+        assert_eq!(classify("JJ455", &ruleset), HandType::groups(&[4, 1]));
+    }
+}
@@ -1,222 +1,115 @@
 use aoclib::parse;
-use counter::Counter;
-use std::{cmp::Reverse, path::Path, str::FromStr};
-
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    parse_display::Display,
-    parse_display::FromStr,
-)]
-enum CardPt1 {
-    #[display("A")]
-    Ace,
-    #[display("K")]
-    King,
-    #[display("Q")]
-    Queen,
-    #[display("J")]
-    Jack,
-    #[display("T")]
-    Ten,
-    #[display("9")]
-    Nine,
-    #[display("8")]
-    Eight,
-    #[display("7")]
-    Seven,
-    #[display("6")]
-    Six,
-    #[display("5")]
-    Five,
-    #[display("4")]
-    Four,
-    #[display("3")]
-    Three,
-    #[display("2")]
-    Two,
+use std::{path::Path, str::FromStr};
+
+pub mod card;
+pub mod explain;
+#[cfg(test)]
+mod fixtures;
+pub mod hand;
+pub mod odds;
+pub mod ruleset;
+pub mod table;
+
+use card::{Card, Suit};
+use hand::Hand;
+use ruleset::Ruleset;
+
+/// A line of the input: some cards, and the bid on them.
+///
+/// Each card may be followed by its suit, as in `2hTs`; either all cards have suits or none do.
+/// Classifying the cards depends on the ruleset, so that happens later.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CardsWithBid {
+    pub cards: Vec<Card>,
+    pub suits: Option<Vec<Suit>>,
+    pub bid: u64,
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    parse_display::Display,
-    parse_display::FromStr,
-)]
-enum CardPt2 {
-    #[display("A")]
-    Ace,
-    #[display("K")]
-    King,
-    #[display("Q")]
-    Queen,
-    #[display("T")]
-    Ten,
-    #[display("9")]
-    Nine,
-    #[display("8")]
-    Eight,
-    #[display("7")]
-    Seven,
-    #[display("6")]
-    Six,
-    #[display("5")]
-    Five,
-    #[display("4")]
-    Four,
-    #[display("3")]
-    Three,
-    #[display("2")]
-    Two,
-    #[display("J")]
-    Joker,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum HandType {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
-    HighCard,
-}
-
-impl From<[CardPt1; 5]> for HandType {
-    fn from(cards: [CardPt1; 5]) -> Self {
-        let counter = cards.into_iter().collect::<Counter<_>>();
-        debug_assert_eq!(counter.values().sum::<usize>(), 5);
-        let frequencies = counter.most_common_ordered();
-        match frequencies.as_slice() {
-            [(_, 5)] => Self::FiveOfAKind,
-            [(_, 4), ..] => Self::FourOfAKind,
-            [(_, 3), (_, 2)] => Self::FullHouse,
-            [(_, 3), ..] => Self::ThreeOfAKind,
-            [(_, 2), (_, 2), ..] => Self::TwoPair,
-            [(_, 2), ..] => Self::OnePair,
-            _ => Self::HighCard,
-        }
-    }
-}
-
-impl From<[CardPt2; 5]> for HandType {
-    fn from(cards: [CardPt2; 5]) -> Self {
-        let mut counter = cards.into_iter().collect::<Counter<_>>();
-        debug_assert_eq!(counter.values().sum::<usize>(), 5);
-        let joker_count = counter.remove(&CardPt2::Joker).unwrap_or_default();
-        let mut frequencies = counter.most_common_ordered();
-        if let Some((_, count)) = frequencies.get_mut(0) {
-            *count += joker_count;
-        } else {
-            frequencies.push((CardPt2::Joker, joker_count));
-        }
-        match frequencies.as_slice() {
-            [(_, 5)] => Self::FiveOfAKind,
-            [(_, 4), ..] => Self::FourOfAKind,
-            [(_, 3), (_, 2)] => Self::FullHouse,
-            [(_, 3), ..] => Self::ThreeOfAKind,
-            [(_, 2), (_, 2), ..] => Self::TwoPair,
-            [(_, 2), ..] => Self::OnePair,
-            _ => Self::HighCard,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Hand<Card> {
-    type_: HandType,
-    cards: [Card; 5],
-}
-
-impl<Card> Hand<Card>
-where
-    Card: std::fmt::Debug + Copy,
-    [Card; 5]: Into<HandType>,
-{
-    fn new(cards: impl IntoIterator<Item = Card>) -> Result<Self, Error> {
-        let cards: [Card; 5] = cards
-            .into_iter()
-            .collect::<Vec<_>>()
-            .try_into()
-            .map_err(|err| Error::Parse(format!("wrong length: {err:?}")))?;
-        let type_ = cards.into();
-        Ok(Hand { type_, cards })
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct HandWithBid<Card> {
-    hand: Hand<Card>,
-    bid: u64,
-}
-
-impl<Card> FromStr for HandWithBid<Card>
-where
-    Card: std::fmt::Debug + FromStr + Copy,
-    Error: From<<Card as FromStr>::Err>,
-    [Card; 5]: Into<HandType>,
-{
+impl FromStr for CardsWithBid {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let Some((hand, bid)) = s.split_once(' ') else {
             return Err(Error::Parse("no space in hand with bid".into()));
         };
+        // suits are lowercase, and no face is
+        let has_suits = hand.chars().any(|c| c.is_ascii_lowercase());
+        let step = if has_suits { 2 } else { 1 };
+        if !hand.is_ascii() || hand.len() % step != 0 {
+            return Err(Error::Parse(format!("malformed hand: {hand}")));
+        }
         let cards = (0..hand.len())
+            .step_by(step)
             .map(|idx| {
                 let s = &hand[idx..idx + 1];
                 s.parse::<Card>()
             })
             .collect::<Result<Vec<_>, _>>()?;
-        let hand = Hand::new(cards)?;
+        let suits = has_suits
+            .then(|| {
+                (1..hand.len())
+                    .step_by(step)
+                    .map(|idx| hand[idx..idx + 1].parse::<Suit>())
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
 
         let bid = bid
             .parse()
             .map_err(|err| Error::Parse(format!("parsing bid: {err}")))?;
 
-        Ok(Self { hand, bid })
+        Ok(Self { cards, suits, bid })
     }
 }
 
-fn compute_total_winnings<Card>(input: &Path, part: u8) -> Result<(), Error>
-where
-    Card: std::fmt::Debug + FromStr + Copy + Ord,
-    Error: From<<Card as FromStr>::Err>,
-    [Card; 5]: Into<HandType>,
-{
-    let mut hand_bids = parse::<HandWithBid<Card>>(input)?.collect::<Vec<_>>();
-    hand_bids.sort_by_key(|hand_bid| Reverse(hand_bid.hand));
-    let total_winnings = hand_bids
+/// Classify every hand under the ruleset, and sort them from weakest to strongest.
+///
+/// A hand's rank is its index in the result, plus 1.
+pub fn rank_hands(
+    hand_bids: &[CardsWithBid],
+    ruleset: &Ruleset,
+) -> Result<Vec<(Hand, u64)>, Error> {
+    let mut hands = hand_bids
+        .iter()
+        .map(|CardsWithBid { cards, suits, bid }| {
+            Ok((Hand::new(cards.clone(), suits.clone(), ruleset)?, *bid))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    hands.sort_by(|(left, _), (right, _)| left.sort_key().cmp(&right.sort_key()));
+    Ok(hands)
+}
+
+/// Rank every hand under the ruleset, and sum each bid multiplied by its hand's rank.
+pub fn total_winnings(hand_bids: &[CardsWithBid], ruleset: &Ruleset) -> Result<u64, Error> {
+    let total_winnings = rank_hands(hand_bids, ruleset)?
         .iter()
         .enumerate()
-        .map(|(idx, HandWithBid { bid, .. })| {
+        .map(|(idx, (_hand, bid))| {
             let rank = idx + 1;
             rank as u64 * *bid
         })
         .sum::<u64>();
-    println!("total winnings (pt {part}): {total_winnings}");
+    Ok(total_winnings)
+}
+
+fn compute_total_winnings(input: &Path, ruleset: &Ruleset, label: &str) -> Result<(), Error> {
+    let hand_bids = parse::<CardsWithBid>(input)?.collect::<Vec<_>>();
+    let total_winnings = total_winnings(&hand_bids, ruleset)?;
+    println!("total winnings ({label}): {total_winnings}");
     Ok(())
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    compute_total_winnings::<CardPt1>(input, 1)
+    compute_total_winnings(input, &Ruleset::part1(), "pt 1")
 }
 
 pub fn part2(input: &Path) -> Result<(), Error> {
-    compute_total_winnings::<CardPt2>(input, 2)
+    compute_total_winnings(input, &Ruleset::part2(), "pt 2")
+}
+
+/// Compute total winnings under a ruleset loaded from `rules`.
+pub fn custom_rules(input: &Path, rules: &Path) -> Result<(), Error> {
+    compute_total_winnings(input, &Ruleset::load(rules)?, "custom rules")
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -234,3 +127,48 @@ impl From<parse_display::ParseError> for Error {
         Self::Parse(value.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    fn example() -> Vec<CardsWithBid> {
+        EXAMPLE.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn pt1_example() {
+        assert_eq!(total_winnings(&example(), &Ruleset::part1()).unwrap(), 6440);
+    }
+
+    #[test]
+    fn pt2_example() {
+        assert_eq!(total_winnings(&example(), &Ruleset::part2()).unwrap(), 5905);
+    }
+
+    #[test]
+    fn parse_suits() {
+        let hand = "2hTs3c3dKs 12".parse::<CardsWithBid>().unwrap();
+        assert_eq!(
+            hand.cards,
+            vec![Card::Two, Card::Ten, Card::Three, Card::Three, Card::King]
+        );
+        assert_eq!(
+            hand.suits,
+            Some(vec![
+                Suit::Hearts,
+                Suit::Spades,
+                Suit::Clubs,
+                Suit::Diamonds,
+                Suit::Spades
+            ])
+        );
+        assert!("2hT3c3dKs 12".parse::<CardsWithBid>().is_err());
+    }
+}
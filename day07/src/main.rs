@@ -1,5 +1,11 @@
 use aoclib::{config::Config, website::get_input};
-use day07::{part1, part2};
+use day07::{
+    custom_rules,
+    explain::explain,
+    odds::{odds, Deck},
+    part1, part2,
+    ruleset::Ruleset,
+};
 
 use clap::Parser;
 use color_eyre::eyre::Result;
@@ -22,6 +28,24 @@ struct RunArgs {
     /// run part 2
     #[arg(long)]
     part2: bool,
+
+    /// also compute total winnings under the ruleset in this file
+    #[arg(long)]
+    rules: Option<PathBuf>,
+
+    /// list every hand in rank order, explaining its type and how it outranks the hand before it
+    #[arg(long)]
+    explain: bool,
+
+    /// print the exact odds of each hand type, given these known cards, under each ruleset
+    ///
+    /// the rest of the hand is drawn from the deck
+    #[arg(long, value_name = "CARDS")]
+    odds: Option<String>,
+
+    /// deck composition for `--odds`, as overrides of four of each card: e.g. `J=8 2=0`
+    #[arg(long, default_value = "")]
+    deck: Deck,
 }
 
 impl RunArgs {
@@ -45,10 +69,32 @@ fn main() -> Result<()> {
     let input_path = args.input()?;
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        if args.explain {
+            explain(&input_path, &Ruleset::part1(), "pt 1")?;
+        } else {
+            part1(&input_path)?;
+        }
     }
     if args.part2 {
-        part2(&input_path)?;
+        if args.explain {
+            explain(&input_path, &Ruleset::part2(), "pt 2")?;
+        } else {
+            part2(&input_path)?;
+        }
+    }
+    if let Some(rules) = &args.rules {
+        if args.explain {
+            explain(&input_path, &Ruleset::load(rules)?, "custom rules")?;
+        } else {
+            custom_rules(&input_path, rules)?;
+        }
+    }
+    if let Some(known) = &args.odds {
+        odds(known, &args.deck, &Ruleset::part1(), "pt 1")?;
+        odds(known, &args.deck, &Ruleset::part2(), "pt 2")?;
+        if let Some(rules) = &args.rules {
+            odds(known, &args.deck, &Ruleset::load(rules)?, "custom rules")?;
+        }
     }
     Ok(())
 }
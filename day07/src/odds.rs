@@ -0,0 +1,267 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    card::Card,
+    hand::HandType,
+    ruleset::{RankKey, Ruleset},
+    Error,
+};
+
+/// How many of each card remain to be drawn.
+///
+/// Suits are not tracked, so drawn hands can never make a flush.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    counts: [u8; Card::COUNT],
+}
+
+impl Default for Deck {
+    /// Four of each card.
+    fn default() -> Self {
+        Self {
+            counts: [4; Card::COUNT],
+        }
+    }
+}
+
+impl Deck {
+    pub fn count(&self, card: Card) -> u8 {
+        self.counts[card.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|&count| count as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove cards which are already known to be in the hand.
+    pub fn remove(&mut self, cards: &[Card]) -> Result<(), Error> {
+        for &card in cards {
+            let count = &mut self.counts[card.index()];
+            *count = count
+                .checked_sub(1)
+                .ok_or_else(|| Error::Parse(format!("more {card} known than the deck holds")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses overrides of the default deck: whitespace-separated `CARD=COUNT`, e.g. `J=8 2=0`.
+impl FromStr for Deck {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut deck = Deck::default();
+        for item in s.split_whitespace() {
+            let Some((card, count)) = item.split_once('=') else {
+                return Err(Error::Parse(format!("expected CARD=COUNT: {item}")));
+            };
+            let card = card.parse::<Card>()?;
+            deck.counts[card.index()] = count
+                .parse()
+                .map_err(|err| Error::Parse(format!("parsing count of {card}: {err}")))?;
+        }
+        Ok(deck)
+    }
+}
+
+fn binomial(n: u128, k: u128) -> u128 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    // each intermediate product is itself a binomial coefficient, so the division is exact
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The exact distribution of hand types after drawing some unknown cards.
+///
+/// Every way of drawing the unknown cards is equally likely; `by_type` counts how many
+/// of those ways end in each type, out of `total`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcomes {
+    pub total: u128,
+    /// Strongest first; types which cannot occur are omitted.
+    pub by_type: Vec<(HandType, u128)>,
+}
+
+impl Outcomes {
+    /// Enumerate every multiset of `draws` cards from the deck, weighting each by how many ways there are to draw it.
+    pub fn enumerate(
+        known: &[Card],
+        deck: &Deck,
+        draws: usize,
+        ruleset: &Ruleset,
+    ) -> Result<Self, Error> {
+        if draws > deck.len() {
+            return Err(Error::Parse(format!(
+                "cannot draw {draws} cards from a deck of {}",
+                deck.len()
+            )));
+        }
+
+        let mut by_type = HashMap::new();
+        let mut hand = known.to_vec();
+        enumerate_draws(deck, draws, 0, 1, &mut hand, ruleset, &mut by_type);
+
+        let mut by_type = by_type
+            .into_iter()
+            .map(|(hand_type, ways)| {
+                let key = ruleset
+                    .rank_key(&hand_type)
+                    .expect("classification only produces ranked categories");
+                (key, hand_type, ways)
+            })
+            .collect::<Vec<(RankKey, HandType, u128)>>();
+        by_type.sort_unstable_by(|left, right| right.0.cmp(&left.0));
+
+        Ok(Self {
+            total: binomial(deck.len() as u128, draws as u128),
+            by_type: by_type
+                .into_iter()
+                .map(|(_key, hand_type, ways)| (hand_type, ways))
+                .collect(),
+        })
+    }
+
+    /// The probability of `ways` out of the total, as a fraction in lowest terms.
+    pub fn fraction(&self, ways: u128) -> (u128, u128) {
+        let divisor = gcd(ways, self.total).max(1);
+        (ways / divisor, self.total / divisor)
+    }
+}
+
+/// Choose how many of each card, from `card_idx` onwards, to draw; `ways` is the number of ways to draw the choices so far.
+fn enumerate_draws(
+    deck: &Deck,
+    remaining: usize,
+    card_idx: usize,
+    ways: u128,
+    hand: &mut Vec<Card>,
+    ruleset: &Ruleset,
+    by_type: &mut HashMap<HandType, u128>,
+) {
+    if remaining == 0 {
+        let hand_type = HandType::classify(hand, None, ruleset);
+        *by_type.entry(hand_type).or_default() += ways;
+        return;
+    }
+    let Some(&card) = Card::ALL.get(card_idx) else {
+        return;
+    };
+
+    let available = deck.count(card) as usize;
+    for n in 0..=available.min(remaining) {
+        let ways = ways * binomial(available as u128, n as u128);
+        hand.extend(std::iter::repeat_n(card, n));
+        enumerate_draws(
+            deck,
+            remaining - n,
+            card_idx + 1,
+            ways,
+            hand,
+            ruleset,
+            by_type,
+        );
+        hand.truncate(hand.len() - n);
+    }
+}
+
+/// Print the distribution of hand types for a hand of which only `known` cards are known so far.
+///
+/// The rest of the hand is drawn from `deck`, less the known cards.
+pub fn odds(known: &str, deck: &Deck, ruleset: &Ruleset, label: &str) -> Result<(), Error> {
+    let known = known
+        .chars()
+        .map(|c| c.to_string().parse::<Card>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let draws = ruleset
+        .hand_size()
+        .checked_sub(known.len())
+        .ok_or_else(|| Error::Parse("more cards known than fit in a hand".into()))?;
+    let mut deck = deck.clone();
+    deck.remove(&known)?;
+
+    let outcomes = Outcomes::enumerate(&known, &deck, draws, ruleset)?;
+    println!(
+        "outcomes ({label}), drawing {draws} from {} cards:",
+        deck.len()
+    );
+    for (hand_type, ways) in &outcomes.by_type {
+        let (numerator, denominator) = outcomes.fraction(*ways);
+        let percent = *ways as f64 / outcomes.total as f64 * 100.0;
+        println!("  {hand_type}: {numerator}/{denominator} ({percent:.4}%)");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::cards;
+
+    fn ways(outcomes: &Outcomes, hand_type: HandType) -> u128 {
+        outcomes
+            .by_type
+            .iter()
+            .find(|(ty, _)| *ty == hand_type)
+            .map_or(0, |(_, ways)| *ways)
+    }
+
+    #[test]
+    fn standard_deck_frequencies() {
+        // the well-known counts of five-card poker hands, with straights and flushes counted by their groups
+        let outcomes = Outcomes::enumerate(&[], &Deck::default(), 5, &Ruleset::part1()).unwrap();
+        assert_eq!(outcomes.total, 2_598_960);
+        assert_eq!(ways(&outcomes, HandType::groups(&[4, 1])), 624);
+        assert_eq!(ways(&outcomes, HandType::groups(&[3, 2])), 3_744);
+        assert_eq!(ways(&outcomes, HandType::groups(&[3, 1, 1])), 54_912);
+        assert_eq!(ways(&outcomes, HandType::groups(&[2, 2, 1])), 123_552);
+        assert_eq!(ways(&outcomes, HandType::groups(&[2, 1, 1, 1])), 1_098_240);
+        assert_eq!(
+            ways(&outcomes, HandType::groups(&[1, 1, 1, 1, 1])),
+            1_317_888
+        );
+        assert_eq!(
+            outcomes.by_type.iter().map(|(_, ways)| ways).sum::<u128>(),
+            outcomes.total
+        );
+    }
+
+    #[test]
+    fn one_card_to_come() {
+        let known = cards("KKQQ");
+        let mut deck = Deck::default();
+        deck.remove(&known).unwrap();
+
+        let outcomes = Outcomes::enumerate(&known, &deck, 1, &Ruleset::part1()).unwrap();
+        assert_eq!(outcomes.total, 48);
+        assert_eq!(ways(&outcomes, HandType::groups(&[3, 2])), 4);
+        assert_eq!(outcomes.fraction(4), (1, 12));
+
+        // with jokers, drawing a J also makes a full house
+        let outcomes = Outcomes::enumerate(&known, &deck, 1, &Ruleset::part2()).unwrap();
+        assert_eq!(ways(&outcomes, HandType::groups(&[3, 2])), 8);
+        assert_eq!(ways(&outcomes, HandType::groups(&[2, 2, 1])), 40);
+    }
+
+    #[test]
+    fn deck_overrides() {
+        let deck = "J=8 2=0".parse::<Deck>().unwrap();
+        assert_eq!(deck.count(Card::Jack), 8);
+        assert_eq!(deck.count(Card::Two), 0);
+        assert_eq!(deck.len(), 52);
+        assert!(Deck::default().remove(&cards("AAAAA")).is_err());
+    }
+}
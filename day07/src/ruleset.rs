@@ -0,0 +1,332 @@
+use std::{path::Path, str::FromStr};
+
+use crate::{
+    card::{Card, CardSet},
+    hand::{format_pattern, HandType, Special},
+    Error,
+};
+
+/// How strongly a [`HandType`] ranks under some ruleset; greater is stronger.
+///
+/// Grouped categories rank by their patterns, compared lexicographically, so
+/// that e.g. `4+1` beats `3+2` beats `3+1+1`. Each enabled special category ranks
+/// just above a particular pattern; several above the same pattern rank in the
+/// order they were enabled.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RankKey {
+    pattern: Vec<u8>,
+    tier: u8,
+}
+
+fn parse_pattern(s: &str) -> Result<Vec<u8>, Error> {
+    let pattern = s
+        .trim()
+        .split('+')
+        .map(|size| {
+            size.trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|&size| size > 0)
+                .ok_or_else(|| Error::Parse(format!("invalid group pattern: {s}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if !pattern.is_sorted_by(|a, b| a >= b) {
+        return Err(Error::Parse(format!(
+            "group pattern must be largest first: {s}"
+        )));
+    }
+    Ok(pattern)
+}
+
+/// How cards are ranked, and which of them are wild.
+///
+/// Rulesets can be loaded from a small config file with one `key = value` per line:
+///
+/// ```text
+/// # every card, weakest first
+/// order = J 2 3 4 5 6 7 8 9 T Q K A
+/// # a wild card which may stand in for any card
+/// wild = J
+/// # a wild card which may only stand in for some cards
+/// wild = 2: K Q
+/// ```
+///
+/// Optionally, hands may have some other number of cards than 5, and special categories
+/// may be enabled, each ranking just above some pattern of grouped faces:
+///
+/// ```text
+/// hand-size = 5
+/// # poker-style: straights and flushes between three of a kind and a full house
+/// straight = above 3+1+1
+/// flush = above 3+1+1
+/// straight-flush = above 4+1
+/// ```
+///
+/// Flushes need suits, which are given by following each card's face with one of `cdhs`.
+///
+/// Blank lines and lines starting with `#` are ignored. `order` is required and must list every card exactly once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    hand_size: usize,
+    /// Enabled special categories, each with the pattern it ranks just above, in the order they were enabled.
+    specials: Vec<(Special, Vec<u8>)>,
+    /// Strength of each card, indexed by [`Card::index`]; higher is stronger.
+    strengths: [u8; Card::COUNT],
+    /// The cards each card may stand in for, indexed by [`Card::index`]; empty when the card is not wild.
+    wild: [CardSet; Card::COUNT],
+}
+
+impl Ruleset {
+    /// Build a ruleset from every card, listed weakest first, and each wild card with what it may stand in for.
+    pub fn new(
+        order: impl IntoIterator<Item = Card>,
+        wild: impl IntoIterator<Item = (Card, CardSet)>,
+    ) -> Result<Self, Error> {
+        let mut strengths = [None; Card::COUNT];
+        for (strength, card) in order.into_iter().enumerate() {
+            if strengths[card.index()].replace(strength as u8).is_some() {
+                return Err(Error::Parse(format!("{card} appears twice in card order")));
+            }
+        }
+        let strengths = strengths
+            .into_iter()
+            .zip(Card::ALL)
+            .map(|(strength, card)| {
+                strength.ok_or_else(|| Error::Parse(format!("{card} missing from card order")))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .expect("one strength per card");
+
+        let mut wild_targets = [CardSet::EMPTY; Card::COUNT];
+        for (card, targets) in wild {
+            if targets.is_empty() {
+                return Err(Error::Parse(format!(
+                    "wild card {card} cannot stand in for anything"
+                )));
+            }
+            if !wild_targets[card.index()].is_empty() {
+                return Err(Error::Parse(format!("{card} declared wild twice")));
+            }
+            wild_targets[card.index()] = targets;
+        }
+
+        Ok(Self {
+            hand_size: 5,
+            specials: Vec::new(),
+            strengths,
+            wild: wild_targets,
+        })
+    }
+
+    /// Set how many cards make a hand.
+    pub fn with_hand_size(mut self, hand_size: usize) -> Result<Self, Error> {
+        if hand_size == 0 {
+            return Err(Error::Parse("hands must have at least one card".into()));
+        }
+        self.hand_size = hand_size;
+        Ok(self)
+    }
+
+    /// Enable a special category, ranking just above hands grouped as `above`.
+    pub fn with_special(mut self, special: Special, above: Vec<u8>) -> Result<Self, Error> {
+        if self.specials.iter().any(|(enabled, _)| *enabled == special) {
+            return Err(Error::Parse(format!("{special} enabled twice")));
+        }
+        self.specials.push((special, above));
+        Ok(self)
+    }
+
+    /// Part 1: aces high, nothing wild.
+    pub fn part1() -> Self {
+        Self::new(Card::ALL, []).expect("standard order is valid")
+    }
+
+    /// Part 2: `J` is a joker, which is the weakest card but may stand in for any card.
+    pub fn part2() -> Self {
+        let order = std::iter::once(Card::Jack)
+            .chain(Card::ALL.into_iter().filter(|&card| card != Card::Jack));
+        Self::new(order, [(Card::Jack, CardSet::ALL)]).expect("joker order is valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn strength(&self, card: Card) -> u8 {
+        self.strengths[card.index()]
+    }
+
+    pub fn is_wild(&self, card: Card) -> bool {
+        !self.wild[card.index()].is_empty()
+    }
+
+    /// The cards which this card may stand in for; empty when it is not wild.
+    pub fn stands_in_for(&self, card: Card) -> CardSet {
+        self.wild[card.index()]
+    }
+
+    pub fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    /// How strongly this category ranks, or `None` if it is a special category which is not enabled.
+    pub fn rank_key(&self, hand_type: &HandType) -> Option<RankKey> {
+        match hand_type {
+            HandType::Groups(pattern) => Some(RankKey {
+                pattern: pattern.clone(),
+                tier: 0,
+            }),
+            HandType::Special(special) => self
+                .specials
+                .iter()
+                .enumerate()
+                .find(|(_idx, (enabled, _above))| enabled == special)
+                .map(|(idx, (_special, above))| RankKey {
+                    pattern: above.clone(),
+                    tier: idx as u8 + 1,
+                }),
+        }
+    }
+}
+
+impl std::fmt::Display for RankKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_pattern(&self.pattern))?;
+        if self.tier > 0 {
+            write!(f, " (+{})", self.tier)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, Error> {
+    s.split_whitespace()
+        .map(|card| card.parse().map_err(Error::from))
+        .collect()
+}
+
+impl FromStr for Ruleset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut order = None;
+        let mut wild = Vec::new();
+        let mut hand_size = None;
+        let mut specials = Vec::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(Error::Parse(format!("expected `key = value`: {line}")));
+            };
+            match key.trim() {
+                "order" => {
+                    if order.replace(parse_cards(value)?).is_some() {
+                        return Err(Error::Parse("card order given twice".into()));
+                    }
+                }
+                "wild" => {
+                    let (card, targets) = match value.split_once(':') {
+                        Some((card, targets)) => {
+                            (card, parse_cards(targets)?.into_iter().collect())
+                        }
+                        None => (value, CardSet::ALL),
+                    };
+                    wild.push((card.trim().parse()?, targets));
+                }
+                "hand-size" => {
+                    let size = value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|err| Error::Parse(format!("parsing hand size: {err}")))?;
+                    if hand_size.replace(size).is_some() {
+                        return Err(Error::Parse("hand size given twice".into()));
+                    }
+                }
+                key => {
+                    let Ok(special) = key.parse::<Special>() else {
+                        return Err(Error::Parse(format!("unknown ruleset key: {key}")));
+                    };
+                    let Some(above) = value.trim().strip_prefix("above ") else {
+                        return Err(Error::Parse(format!(
+                            "expected `{special} = above <pattern>`"
+                        )));
+                    };
+                    specials.push((special, parse_pattern(above)?));
+                }
+            }
+        }
+
+        let order = order.ok_or_else(|| Error::Parse("no card order given".into()))?;
+        let mut ruleset = Self::new(order, wild)?;
+        if let Some(hand_size) = hand_size {
+            ruleset = ruleset.with_hand_size(hand_size)?;
+        }
+        for (special, above) in specials {
+            ruleset = ruleset.with_special(special, above)?;
+        }
+        Ok(ruleset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_matches_builtin() {
+        let part1 = "order = 2 3 4 5 6 7 8 9 T J Q K A"
+            .parse::<Ruleset>()
+            .unwrap();
+        assert_eq!(part1, Ruleset::part1());
+
+        let part2 = "
+            # jokers are wild
+            order = J 2 3 4 5 6 7 8 9 T Q K A
+            wild = J
+        "
+        .parse::<Ruleset>()
+        .unwrap();
+        assert_eq!(part2, Ruleset::part2());
+    }
+
+    #[test]
+    fn restricted_wild() {
+        let ruleset = "order = A 2 3 4 5 6 7 8 9 T J Q K\nwild = 2: K Q"
+            .parse::<Ruleset>()
+            .unwrap();
+        assert!(ruleset.strength(Card::Ace) < ruleset.strength(Card::Two));
+        assert!(ruleset.is_wild(Card::Two));
+        assert_eq!(
+            ruleset.stands_in_for(Card::Two).iter().collect::<Vec<_>>(),
+            vec![Card::Queen, Card::King]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_configs() {
+        assert!("order = 2 3 4".parse::<Ruleset>().is_err());
+        assert!("order = 2 2 3 4 5 6 7 8 9 T J Q K A"
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("wild = J".parse::<Ruleset>().is_err());
+        assert!("order = 2 3 4 5 6 7 8 9 T J Q K A\nwild = J:"
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("order = 2 3 4 5 6 7 8 9 T J Q K A\nsuits = yes"
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("order = 2 3 4 5 6 7 8 9 T J Q K A\nflush = 3+1+1"
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("order = 2 3 4 5 6 7 8 9 T J Q K A\nflush = above 1+3"
+            .parse::<Ruleset>()
+            .is_err());
+        assert!("order = 2 3 4 5 6 7 8 9 T J Q K A\nhand-size = 0"
+            .parse::<Ruleset>()
+            .is_err());
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use crate::{
+    card::{Card, Suit},
+    hand::{is_flush, HandType, Special},
+    ruleset::Ruleset,
+    CardsWithBid, Error,
+};
+
+/// The most cards per hand which a [`HandTable`] supports; its table has `16^n` entries per suit case.
+pub const MAX_TABLE_HAND_SIZE: usize = 5;
+
+const BITS_PER_CARD: usize = 4;
+
+/// Pack cards into an integer, [`BITS_PER_CARD`] bits per card by [`Card::index`], first card most significant.
+pub fn pack(cards: &[Card]) -> u32 {
+    cards.iter().fold(0, |packed, card| {
+        packed << BITS_PER_CARD | card.index() as u32
+    })
+}
+
+/// Every hand's type under a ruleset, precomputed.
+///
+/// Classifying by exhaustive wild assignment is flexible but slow; for rulesets with
+/// small enough hands, it is much cheaper to classify every possible hand once, up front,
+/// and then look each hand up by its [packed](pack) cards.
+#[derive(Debug, Clone)]
+pub struct HandTable {
+    ruleset: Ruleset,
+    /// Every type which some hand classifies as, weakest first.
+    types: Vec<HandType>,
+    /// For each packed hand, the index into `types` of its type.
+    ///
+    /// When the ruleset enables flushes, the table is doubled: the upper half is for hands
+    /// which are flushes.
+    lookup: Vec<u8>,
+    flushes: bool,
+}
+
+impl HandTable {
+    pub fn new(ruleset: &Ruleset) -> Result<Self, Error> {
+        let hand_size = ruleset.hand_size();
+        if hand_size > MAX_TABLE_HAND_SIZE {
+            return Err(Error::Parse(format!(
+                "lookup tables support at most {MAX_TABLE_HAND_SIZE} cards per hand, not {hand_size}"
+            )));
+        }
+        let flushes = [Special::Flush, Special::StraightFlush]
+            .into_iter()
+            .any(|special| ruleset.rank_key(&HandType::Special(special)).is_some());
+        let flush_suits = vec![Suit::Clubs; hand_size];
+
+        let n_hands = Card::COUNT.pow(hand_size as u32);
+        let hand = |mut code: usize| {
+            let mut cards = vec![Card::Two; hand_size];
+            for card in cards.iter_mut().rev() {
+                *card = Card::ALL[code % Card::COUNT];
+                code /= Card::COUNT;
+            }
+            cards
+        };
+
+        // type depends only on which cards are in the hand, so classify each multiset once, via its sorted ordering
+        let mut by_multiset = HashMap::new();
+        for code in 0..n_hands {
+            let cards = hand(code);
+            if cards.is_sorted() {
+                let plain = HandType::classify(&cards, None, ruleset);
+                let flush =
+                    flushes.then(|| HandType::classify(&cards, Some(&flush_suits), ruleset));
+                by_multiset.insert(pack(&cards), (plain, flush));
+            }
+        }
+
+        let mut types = by_multiset
+            .values()
+            .flat_map(|(plain, flush)| std::iter::once(plain).chain(flush))
+            .map(|hand_type| {
+                let key = ruleset
+                    .rank_key(hand_type)
+                    .expect("classification only produces ranked categories");
+                (key, hand_type.clone())
+            })
+            .collect::<Vec<_>>();
+        // distinct types never share a rank
+        types.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+        types.dedup_by(|(left, _), (right, _)| left == right);
+        let types = types
+            .into_iter()
+            .map(|(_key, hand_type)| hand_type)
+            .collect::<Vec<_>>();
+        let ordinal = |hand_type: &HandType| {
+            types
+                .iter()
+                .position(|known| known == hand_type)
+                .expect("every classified type is known") as u8
+        };
+        let ordinals = by_multiset
+            .into_iter()
+            .map(|(packed, (plain, flush))| {
+                (packed, (ordinal(&plain), flush.as_ref().map(ordinal)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // entries for packings which aren't hands are never read
+        let half = 1 << (BITS_PER_CARD * hand_size);
+        let mut lookup = vec![u8::MAX; if flushes { 2 * half } else { half }];
+        for code in 0..n_hands {
+            let cards = hand(code);
+            let mut sorted = cards.clone();
+            sorted.sort_unstable();
+            let (plain, flush) = ordinals[&pack(&sorted)];
+            let packed = pack(&cards) as usize;
+            lookup[packed] = plain;
+            if let Some(flush) = flush {
+                lookup[half + packed] = flush;
+            }
+        }
+
+        Ok(Self {
+            ruleset: ruleset.clone(),
+            types,
+            lookup,
+            flushes,
+        })
+    }
+
+    fn ordinal(&self, cards: &[Card], suits: Option<&[Suit]>) -> Result<u8, Error> {
+        let hand_size = self.ruleset.hand_size();
+        if cards.len() != hand_size {
+            return Err(Error::Parse(format!(
+                "wrong length: expected {hand_size} cards but got {}",
+                cards.len()
+            )));
+        }
+        let mut idx = pack(cards) as usize;
+        if self.flushes && is_flush(cards, suits, &self.ruleset) {
+            idx += 1 << (BITS_PER_CARD * hand_size);
+        }
+        Ok(self.lookup[idx])
+    }
+
+    /// Look up a hand's type, as [`HandType::classify`] would classify it.
+    pub fn hand_type(&self, cards: &[Card], suits: Option<&[Suit]>) -> Result<&HandType, Error> {
+        Ok(&self.types[self.ordinal(cards, suits)? as usize])
+    }
+
+    /// The hand packed into a single integer, which orders hands as [`Hand::sort_key`](crate::hand::Hand::sort_key) does.
+    ///
+    /// The type's rank is in the most significant bits, followed by each card's strength in turn.
+    pub fn sort_key(&self, cards: &[Card], suits: Option<&[Suit]>) -> Result<u32, Error> {
+        let ordinal = self.ordinal(cards, suits)?;
+        Ok(cards.iter().fold(ordinal as u32, |key, &card| {
+            key << BITS_PER_CARD | self.ruleset.strength(card) as u32
+        }))
+    }
+
+    /// As [`crate::total_winnings`], ranking hands by their packed sort keys.
+    pub fn total_winnings(&self, hand_bids: &[CardsWithBid]) -> Result<u64, Error> {
+        let mut keys = hand_bids
+            .iter()
+            .map(|CardsWithBid { cards, suits, bid }| {
+                Ok((self.sort_key(cards, suits.as_deref())?, *bid))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        // stable, so that equal hands keep their input order, as in `total_winnings`
+        keys.sort_by_key(|&(key, _bid)| key);
+        Ok(keys
+            .iter()
+            .enumerate()
+            .map(|(idx, (_key, bid))| (idx as u64 + 1) * bid)
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::cards, hand::Hand, total_winnings};
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+2h3h4h5h7h 100
+2h3h4h5h6c 5";
+
+    fn example() -> Vec<CardsWithBid> {
+        EXAMPLE.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    fn rulesets() -> Vec<Ruleset> {
+        let poker = "
+            order = 2 3 4 5 6 7 8 9 T J Q K A
+            straight = above 3+1+1
+            flush = above 3+1+1
+            straight-flush = above 4+1
+        ";
+        let wild_poker = "
+            order = J 2 3 4 5 6 7 8 9 T Q K A
+            wild = J
+            wild = 2: K Q
+            flush = above 3+2
+        ";
+        vec![
+            Ruleset::part1(),
+            Ruleset::part2(),
+            poker.parse().unwrap(),
+            wild_poker.parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn matches_classification() {
+        for ruleset in rulesets() {
+            let table = HandTable::new(&ruleset).unwrap();
+            let hand_bids = example();
+            for CardsWithBid { cards, suits, .. } in &hand_bids {
+                assert_eq!(
+                    table.hand_type(cards, suits.as_deref()).unwrap(),
+                    &HandType::classify(cards, suits.as_deref(), &ruleset)
+                );
+            }
+            assert_eq!(
+                table.total_winnings(&hand_bids).unwrap(),
+                total_winnings(&hand_bids, &ruleset).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn sort_keys_agree() {
+        let ruleset = Ruleset::part2();
+        let table = HandTable::new(&ruleset).unwrap();
+        let hands = [
+            "JJJJJ", "JJJJ2", "2222J", "AAKKJ", "AAKKQ", "2345J", "23456",
+        ]
+        .map(cards);
+        for left in &hands {
+            for right in &hands {
+                let hand = |cards: &[Card]| Hand::new(cards.to_vec(), None, &ruleset).unwrap();
+                assert_eq!(
+                    table
+                        .sort_key(left, None)
+                        .unwrap()
+                        .cmp(&table.sort_key(right, None).unwrap()),
+                    hand(left).sort_key().cmp(&hand(right).sort_key()),
+                    "{left:?} vs {right:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hand_size_limits() {
+        let ruleset = Ruleset::part1().with_hand_size(6).unwrap();
+        assert!(HandTable::new(&ruleset).is_err());
+
+        let ruleset = Ruleset::part1().with_hand_size(3).unwrap();
+        let table = HandTable::new(&ruleset).unwrap();
+        assert_eq!(
+            table
+                .hand_type(&[Card::Ace, Card::Two, Card::Ace], None)
+                .unwrap(),
+            &HandType::groups(&[2, 1])
+        );
+        assert!(table.hand_type(&[Card::Ace], None).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day08::{
+    cycles::Cycle,
+    input::NodeDefinition,
+    models::{Direction, Network},
+};
+
+const N_NODES: usize = 5_000;
+const N_DIRECTIONS: usize = 300;
+
+/// A tiny linear congruential generator, so that the benchmark input is deterministic.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize
+    }
+}
+
+/// Three-character names in the puzzle's style, some of which end in `A` or `Z`.
+fn name(idx: usize) -> String {
+    const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut idx = idx;
+    let mut name = [0; 3];
+    for byte in name.iter_mut().rev() {
+        *byte = ALPHABET[idx % ALPHABET.len()];
+        idx /= ALPHABET.len();
+    }
+    String::from_utf8(name.to_vec()).unwrap()
+}
+
+fn generate() -> (Vec<Direction>, Vec<String>) {
+    let mut rng = Lcg(0x5eed);
+    let directions = (0..N_DIRECTIONS)
+        .map(|_| {
+            if rng.next().is_multiple_of(2) {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        })
+        .collect();
+    let lines = (0..N_NODES)
+        .map(|idx| {
+            let left = name(rng.next() % N_NODES);
+            let right = name(rng.next() % N_NODES);
+            format!("{} = ({left}, {right})", name(idx))
+        })
+        .collect();
+    (directions, lines)
+}
+
+/// The original representation, which mapped between names and positions with `HashMap`s.
+struct HashMapNetwork {
+    names: HashMap<String, usize>,
+    name_for: HashMap<usize, String>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+#[derive(parse_display::FromStr)]
+#[display("{name} = ({left}, {right})")]
+struct StringDefinition {
+    name: String,
+    left: String,
+    right: String,
+}
+
+impl HashMapNetwork {
+    fn parse(lines: &[String]) -> Self {
+        let definitions = lines
+            .iter()
+            .map(|line| line.parse::<StringDefinition>().unwrap())
+            .collect::<Vec<_>>();
+        let mut names = HashMap::with_capacity(definitions.len());
+        let mut name_for = HashMap::with_capacity(definitions.len());
+        for (idx, definition) in definitions.iter().enumerate() {
+            names.insert(definition.name.clone(), idx);
+            name_for.insert(idx, definition.name.clone());
+        }
+        let left = definitions
+            .iter()
+            .map(|definition| names[&definition.left])
+            .collect();
+        let right = definitions
+            .iter()
+            .map(|definition| names[&definition.right])
+            .collect();
+        Self {
+            names,
+            name_for,
+            left,
+            right,
+        }
+    }
+
+    fn step(&self, position: usize, direction: Direction) -> usize {
+        match direction {
+            Direction::Left => self.left[position],
+            Direction::Right => self.right[position],
+        }
+    }
+
+    fn position_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    fn name_of(&self, position: usize) -> Option<&str> {
+        self.name_for.get(&position).map(String::as_str)
+    }
+}
+
+fn parse(lines: &[String]) -> Network {
+    lines
+        .iter()
+        .map(|line| line.parse::<NodeDefinition>().unwrap())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let (_directions, lines) = generate();
+    let mut group = c.benchmark_group("parse network");
+    group.bench_function("hashmap", |b| {
+        b.iter(|| HashMapNetwork::parse(black_box(&lines)))
+    });
+    group.bench_function("packed codes", |b| b.iter(|| parse(black_box(&lines))));
+    group.finish();
+}
+
+fn bench_simulate(c: &mut Criterion) {
+    let (directions, lines) = generate();
+    let network = parse(&lines);
+    let starts = network
+        .names()
+        .filter(|name| name.ends_with('A'))
+        .map(|name| network.position_of(name).unwrap())
+        .take(6)
+        .collect::<Vec<_>>();
+
+    c.bench_function("detect ghost cycles", |b| {
+        b.iter(|| {
+            starts
+                .iter()
+                .map(|&start| {
+                    Cycle::detect(&network, black_box(&directions), start, |position| {
+                        network.name_of(position).unwrap().ends_with('Z')
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let mut group = c.benchmark_group("step and look up names");
+    let reference = HashMapNetwork::parse(&lines);
+    let reference_start = reference
+        .position_of(network.name_of(starts[0]).unwrap())
+        .unwrap();
+    group.bench_function("hashmap", |b| {
+        b.iter(|| {
+            let mut position = reference_start;
+            let mut goals = 0_usize;
+            for &direction in directions.iter().cycle().take(1_000_000) {
+                position = reference.step(position, direction);
+                goals += usize::from(reference.name_of(position).unwrap().ends_with('Z'));
+            }
+            goals
+        })
+    });
+    group.bench_function("packed codes", |b| {
+        b.iter(|| {
+            let mut position = starts[0];
+            let mut goals = 0_usize;
+            for &direction in directions.iter().cycle().take(1_000_000) {
+                position = network.step(position, direction);
+                goals += usize::from(network.name_of(position).unwrap().ends_with('Z'));
+            }
+            goals
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_simulate);
+criterion_main!(benches);
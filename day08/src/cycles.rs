@@ -0,0 +1,243 @@
+use crate::{
+    crt2, lcm,
+    models::{Direction, Network},
+};
+
+/// The long-run behavior of a single ghost's walk through the network.
+///
+/// The walk's state is `(node, instruction index)`. There are finitely many states,
+/// so every walk eventually settles into a cycle, possibly after some initial tail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// The number of steps before the walk first enters its cycle.
+    pub tail_length: u64,
+    /// The number of steps in one repetition of the cycle.
+    pub cycle_length: u64,
+    /// Steps at which the walk is on a goal node before entering its cycle.
+    ///
+    /// Sorted; every item is less than `tail_length`.
+    pub tail_hits: Vec<u64>,
+    /// Steps at which the walk is on a goal node during its first traversal of the cycle.
+    ///
+    /// Sorted; every item is in `tail_length..tail_length + cycle_length`. Every later
+    /// goal hit is one of these plus some multiple of `cycle_length`.
+    pub cycle_hits: Vec<u64>,
+    /// The distinct goal nodes which the walk ever visits, sorted by position.
+    pub goal_nodes: Vec<usize>,
+}
+
+impl Cycle {
+    /// Walk from `start` until a state repeats, recording every step at which `is_goal` holds.
+    pub fn detect(
+        network: &Network,
+        directions: &[Direction],
+        start: usize,
+        is_goal: impl Fn(usize) -> bool,
+    ) -> Self {
+        debug_assert!(!directions.is_empty(), "parsing rejects empty directions");
+
+        // index: `position * directions.len() + instruction_idx`
+        let mut first_seen = vec![None; network.len() * directions.len()];
+        let mut hits = Vec::new();
+        let mut goal_nodes = Vec::new();
+        let mut position = start;
+        let mut steps = 0_u64;
+
+        let (tail_length, cycle_length) = loop {
+            let instruction_idx = (steps % directions.len() as u64) as usize;
+            let state = position * directions.len() + instruction_idx;
+            if let Some(seen_at) = first_seen[state] {
+                break (seen_at, steps - seen_at);
+            }
+            first_seen[state] = Some(steps);
+
+            if is_goal(position) {
+                hits.push(steps);
+                goal_nodes.push(position);
+            }
+            position = network.step(position, directions[instruction_idx]);
+            steps += 1;
+        };
+
+        let cycle_start = hits.partition_point(|&hit| hit < tail_length);
+        let cycle_hits = hits.split_off(cycle_start);
+        goal_nodes.sort_unstable();
+        goal_nodes.dedup();
+        Self {
+            tail_length,
+            cycle_length,
+            tail_hits: hits,
+            cycle_hits,
+            goal_nodes,
+        }
+    }
+
+    /// The first step at which the walk is on a goal node, if it ever is.
+    pub fn first_hit(&self) -> Option<u64> {
+        self.tail_hits
+            .first()
+            .or_else(|| self.cycle_hits.first())
+            .copied()
+    }
+
+    /// `true` if the walk is on a goal node after exactly `steps` steps.
+    pub fn is_goal_at(&self, steps: u64) -> bool {
+        if steps < self.tail_length {
+            self.tail_hits.binary_search(&steps).is_ok()
+        } else {
+            let offset = (steps - self.tail_length) % self.cycle_length;
+            self.cycle_hits
+                .binary_search(&(self.tail_length + offset))
+                .is_ok()
+        }
+    }
+
+    /// `true` if this walk is on a goal node at exactly the positive multiples of `cycle_length`.
+    ///
+    /// When this holds for every ghost, the first step at which they are all on a goal is
+    /// the LCM of their cycle lengths.
+    pub fn lcm_is_valid(&self) -> bool {
+        self.tail_hits.is_empty() && self.cycle_hits == [self.cycle_length]
+    }
+}
+
+/// Search every combination of cycle hits for the earliest step, at or after `earliest`, at which every ghost is on a goal.
+fn earliest_common_cycle_hit(
+    cycles: &[Cycle],
+    residue: i128,
+    modulus: i128,
+    earliest: i128,
+    best: &mut Option<i128>,
+) {
+    let Some((cycle, rest)) = cycles.split_first() else {
+        // the smallest step at or after `earliest` which satisfies all the congruences
+        let steps = if residue >= earliest {
+            residue
+        } else {
+            let periods = (earliest - residue + modulus - 1) / modulus;
+            residue + periods * modulus
+        };
+        *best = Some(best.map_or(steps, |best| best.min(steps)));
+        return;
+    };
+
+    let cycle_length = cycle.cycle_length as i128;
+    for &hit in &cycle.cycle_hits {
+        // the congruence is only inconsistent when the ghosts can never simultaneously land on these hits
+        if let Some((residue, modulus)) =
+            crt2(residue, modulus, hit as i128 % cycle_length, cycle_length)
+        {
+            earliest_common_cycle_hit(rest, residue, modulus, earliest, best);
+        }
+    }
+}
+
+/// Find the first step at which every ghost is simultaneously on a goal node.
+///
+/// Uses the LCM of the cycle lengths only when [`Cycle::lcm_is_valid`] holds for every ghost;
+/// otherwise combines the ghosts' tails and cycles with the generalized Chinese remainder theorem.
+///
+/// `None` if the ghosts never all reach a goal at the same time.
+pub fn first_common_goal(cycles: &[Cycle]) -> Option<u128> {
+    if cycles.is_empty() {
+        return None;
+    }
+
+    if cycles.iter().all(Cycle::lcm_is_valid) {
+        let cycle_lengths = cycles
+            .iter()
+            .map(|cycle| cycle.cycle_length as u128)
+            .collect::<Vec<_>>();
+        return Some(lcm(&cycle_lengths));
+    }
+
+    // while the ghost with the longest tail is still in its tail, it can only be on a goal at one of its tail hits
+    let longest_tail = cycles.iter().max_by_key(|cycle| cycle.tail_length)?;
+    if let Some(steps) = longest_tail
+        .tail_hits
+        .iter()
+        .copied()
+        .find(|&steps| cycles.iter().all(|cycle| cycle.is_goal_at(steps)))
+    {
+        return Some(steps as u128);
+    }
+
+    // after that, every ghost is in its cycle
+    let mut best = None;
+    earliest_common_cycle_hit(cycles, 0, 1, longest_tail.tail_length as i128, &mut best);
+    best.map(|steps| steps as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{
+        ghost_starts, is_ghost_goal, network, LCM_WOULD_BE_WRONG, NEVER_SIMULTANEOUS, PT2_EXAMPLE,
+    };
+
+    fn cycles(network: &Network, directions: &[Direction]) -> Vec<Cycle> {
+        ghost_starts(network)
+            .into_iter()
+            .map(|start| Cycle::detect(network, directions, start, is_ghost_goal(network)))
+            .collect()
+    }
+
+    #[test]
+    fn pt2_example() {
+        let network = network(PT2_EXAMPLE);
+        let cycles = cycles(&network, &[Direction::Left, Direction::Right]);
+        assert_eq!(
+            cycles[0],
+            Cycle {
+                tail_length: 1,
+                cycle_length: 2,
+                tail_hits: Vec::new(),
+                cycle_hits: vec![2],
+                goal_nodes: vec![network.position_of("11Z").unwrap()],
+            }
+        );
+        assert_eq!(
+            cycles[1],
+            Cycle {
+                tail_length: 1,
+                cycle_length: 6,
+                tail_hits: Vec::new(),
+                cycle_hits: vec![3, 6],
+                goal_nodes: vec![network.position_of("22Z").unwrap()],
+            }
+        );
+        assert_eq!(first_common_goal(&cycles), Some(6));
+    }
+
+    #[test]
+    fn lcm_would_be_wrong() {
+        let network = network(LCM_WOULD_BE_WRONG);
+        let cycles = cycles(&network, &[Direction::Left]);
+        assert!(!cycles.iter().any(Cycle::lcm_is_valid));
+        assert_eq!(first_common_goal(&cycles), Some(11));
+    }
+
+    #[test]
+    fn never_simultaneous() {
+        let network = network(NEVER_SIMULTANEOUS);
+        let cycles = cycles(&network, &[Direction::Left]);
+        assert_eq!(first_common_goal(&cycles), None);
+    }
+
+    #[test]
+    fn common_hit_in_tail() {
+        // PPA only reaches a goal once, before it enters its cycle
+        let network = network(
+            "PPA = (PPB, PPB)
+PPB = (PPZ, PPZ)
+PPZ = (PPC, PPC)
+PPC = (PPC, PPC)
+QQA = (QQZ, QQZ)
+QQZ = (QQZ, QQZ)",
+        );
+        let cycles = cycles(&network, &[Direction::Left]);
+        assert_eq!(cycles[0].tail_hits, vec![2]);
+        assert!(cycles[0].cycle_hits.is_empty());
+        assert_eq!(first_common_goal(&cycles), Some(2));
+    }
+}
@@ -0,0 +1,222 @@
+use std::path::Path;
+
+use crate::{
+    cycles, cycles::Cycle, endpoints::Endpoints, ghost_cycles, input, lcm, models::Network, Error,
+};
+
+/// The properties of a ghost's walk which the LCM shortcut silently depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assumptions {
+    /// The walk never reaches a goal before entering its cycle.
+    pub no_tail_hits: bool,
+    /// The first goal hit is exactly one cycle length from the start.
+    pub first_hit_is_cycle_length: bool,
+    /// Within its cycle, the walk is on a goal exactly once, at the end of the first repetition.
+    pub one_hit_per_cycle: bool,
+    /// The walk only ever visits a single goal node.
+    pub single_goal_node: bool,
+    /// The cycle consists of a whole number of passes over the directions.
+    pub aligned_with_directions: bool,
+}
+
+impl Assumptions {
+    pub fn check(cycle: &Cycle, n_directions: usize) -> Self {
+        Self {
+            no_tail_hits: cycle.tail_hits.is_empty(),
+            first_hit_is_cycle_length: cycle.first_hit() == Some(cycle.cycle_length),
+            one_hit_per_cycle: cycle.cycle_hits == [cycle.cycle_length],
+            single_goal_node: cycle.goal_nodes.len() == 1,
+            aligned_with_directions: cycle.cycle_length.is_multiple_of(n_directions as u64),
+        }
+    }
+
+    pub fn all_hold(&self) -> bool {
+        self.no_tail_hits
+            && self.first_hit_is_cycle_length
+            && self.one_hit_per_cycle
+            && self.single_goal_node
+            && self.aligned_with_directions
+    }
+}
+
+fn check_mark(holds: bool) -> &'static str {
+    if holds {
+        "ok"
+    } else {
+        "FAIL"
+    }
+}
+
+fn report_ghost(network: &Network, n_directions: usize, name: &str, cycle: &Cycle) -> bool {
+    let assumptions = Assumptions::check(cycle, n_directions);
+    let goal_names = cycle
+        .goal_nodes
+        .iter()
+        .map(|&position| {
+            network
+                .name_of(position)
+                .expect("all valid positions have names")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let first_hit = cycle
+        .first_hit()
+        .map_or_else(|| "never".to_string(), |hit| hit.to_string());
+
+    println!(
+        "ghost {name}: tail {}, cycle {}, first goal at {first_hit}, goals visited: [{goal_names}]",
+        cycle.tail_length, cycle.cycle_length,
+    );
+    println!(
+        "  {:4} no goal hits before the cycle",
+        check_mark(assumptions.no_tail_hits)
+    );
+    println!(
+        "  {:4} first goal hit equals cycle length",
+        check_mark(assumptions.first_hit_is_cycle_length)
+    );
+    println!(
+        "  {:4} exactly one goal hit per cycle",
+        check_mark(assumptions.one_hit_per_cycle)
+    );
+    println!(
+        "  {:4} visits exactly one goal node",
+        check_mark(assumptions.single_goal_node)
+    );
+    println!(
+        "  {:4} cycle length is a multiple of the {n_directions} directions",
+        check_mark(assumptions.aligned_with_directions)
+    );
+
+    assumptions.all_hold()
+}
+
+/// Check, per ghost, the assumptions which make the LCM shortcut valid, and explain what happens when they don't hold.
+pub fn diagnose(input: &Path, endpoints: &Endpoints) -> Result<(), Error> {
+    let (directions, network) = input::parse(input)?;
+    let ghosts = ghost_cycles(&network, &directions, endpoints)?;
+
+    let mut all_hold = true;
+    for (name, cycle) in &ghosts {
+        all_hold &= report_ghost(&network, directions.len(), name, cycle);
+    }
+
+    let cycles = ghosts
+        .into_iter()
+        .map(|(_name, cycle)| cycle)
+        .collect::<Vec<_>>();
+    let answer = cycles::first_common_goal(&cycles);
+
+    if all_hold {
+        // every ghost is on a goal at exactly the multiples of its cycle length, so the answer is the LCM
+        let Some(answer) = answer else {
+            return Err(Error::NoSolution);
+        };
+        println!("all assumptions hold; first simultaneous goal: {answer}");
+        return Ok(());
+    }
+
+    // this is what the original solution computed: the LCM of each ghost's first goal hit
+    let first_hits = cycles
+        .iter()
+        .map(|cycle| cycle.first_hit().map(u128::from))
+        .collect::<Option<Vec<_>>>();
+    let naive = first_hits.map_or_else(
+        || "no answer, as some ghost never reaches a goal".to_string(),
+        |first_hits| lcm(&first_hits).to_string(),
+    );
+    let answer = answer.map_or_else(|| "never happens".to_string(), |answer| answer.to_string());
+    println!("some assumptions are violated, so the LCM shortcut cannot be trusted");
+    println!("  LCM of first goal hits: {naive}");
+    println!("  first simultaneous goal: {answer}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{ghost_starts, is_ghost_goal, network},
+        models::Direction,
+    };
+
+    fn cycle(
+        tail_length: u64,
+        cycle_length: u64,
+        cycle_hits: &[u64],
+        goal_nodes: &[usize],
+    ) -> Cycle {
+        Cycle {
+            tail_length,
+            cycle_length,
+            tail_hits: Vec::new(),
+            cycle_hits: cycle_hits.to_vec(),
+            goal_nodes: goal_nodes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn well_behaved_ghost() {
+        let assumptions = Assumptions::check(&cycle(3, 12, &[12], &[7]), 4);
+        assert!(assumptions.all_hold());
+    }
+
+    #[test]
+    fn first_hit_before_cycle_length() {
+        let assumptions = Assumptions::check(&cycle(1, 6, &[3, 6], &[7]), 2);
+        assert_eq!(
+            assumptions,
+            Assumptions {
+                no_tail_hits: true,
+                first_hit_is_cycle_length: false,
+                one_hit_per_cycle: false,
+                single_goal_node: true,
+                aligned_with_directions: true,
+            }
+        );
+    }
+
+    #[test]
+    fn ghost_with_several_goals() {
+        let assumptions = Assumptions::check(&cycle(1, 5, &[4, 5], &[7, 8]), 2);
+        assert_eq!(
+            assumptions,
+            Assumptions {
+                no_tail_hits: true,
+                first_hit_is_cycle_length: false,
+                one_hit_per_cycle: false,
+                single_goal_node: false,
+                aligned_with_directions: false,
+            }
+        );
+    }
+
+    #[test]
+    fn goal_hits_only_in_tails() {
+        // each ghost's first hit is one cycle length in, but in its tail: they never coincide
+        let network = network(
+            "11A = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11C, 11C)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22D, 22D)
+22D = (22C, 22C)",
+        );
+        let directions = [Direction::Left];
+        let cycles = ghost_starts(&network)
+            .into_iter()
+            .map(|start| Cycle::detect(&network, &directions, start, is_ghost_goal(&network)))
+            .collect::<Vec<_>>();
+        for cycle in &cycles {
+            let assumptions = Assumptions::check(cycle, directions.len());
+            assert!(assumptions.first_hit_is_cycle_length);
+            assert!(assumptions.single_goal_node);
+            assert!(assumptions.aligned_with_directions);
+            assert!(!assumptions.no_tail_hits);
+            assert!(!assumptions.all_hold());
+        }
+        assert_eq!(cycles::first_common_goal(&cycles), None);
+    }
+}
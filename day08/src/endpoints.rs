@@ -0,0 +1,172 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use crate::{
+    models::{Network, INITIAL_NAME, TARGET_NAME},
+    Error,
+};
+
+/// A node name, or a glob pattern over node names.
+///
+/// `*` matches any run of characters, including none; `?` matches exactly one character.
+/// Everything else matches itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodePattern(String);
+
+impl NodePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        let pattern = self.0.chars().collect::<Vec<_>>();
+        let name = name.chars().collect::<Vec<_>>();
+
+        // greedy matching, backtracking only to the most recent `*`
+        let (mut p, mut n) = (0, 0);
+        let mut last_star = None;
+        while n < name.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    last_star = Some((p, n));
+                    p += 1;
+                }
+                Some(&c) if c == '?' || c == name[n] => {
+                    p += 1;
+                    n += 1;
+                }
+                _ => match last_star {
+                    // let the star absorb one more character, and try again
+                    Some((star, star_n)) => {
+                        last_star = Some((star, star_n + 1));
+                        p = star + 1;
+                        n = star_n + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+impl FromStr for NodePattern {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_owned()))
+    }
+}
+
+impl fmt::Display for NodePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Which nodes walks begin from, and which nodes they are trying to reach.
+///
+/// A node is a start or goal if it matches any of the respective patterns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    pub starts: Vec<NodePattern>,
+    pub goals: Vec<NodePattern>,
+}
+
+impl Endpoints {
+    fn pattern(s: &str) -> NodePattern {
+        NodePattern(s.to_owned())
+    }
+
+    /// Part 1 walks from `AAA` to `ZZZ`.
+    pub fn part1() -> Self {
+        Self {
+            starts: vec![Self::pattern(INITIAL_NAME)],
+            goals: vec![Self::pattern(TARGET_NAME)],
+        }
+    }
+
+    /// Part 2's ghosts walk from every node ending in `A` to any node ending in `Z`.
+    pub fn part2() -> Self {
+        Self {
+            starts: vec![Self::pattern("*A")],
+            goals: vec![Self::pattern("*Z")],
+        }
+    }
+
+    /// Replace the start and goal patterns with these, where any are given.
+    pub fn with_overrides(mut self, starts: &[NodePattern], goals: &[NodePattern]) -> Self {
+        if !starts.is_empty() {
+            self.starts = starts.to_vec();
+        }
+        if !goals.is_empty() {
+            self.goals = goals.to_vec();
+        }
+        self
+    }
+
+    pub fn is_start(&self, name: &str) -> bool {
+        self.starts.iter().any(|pattern| pattern.matches(name))
+    }
+
+    pub fn is_goal(&self, name: &str) -> bool {
+        self.goals.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// The name and position of every start node, sorted by name.
+    ///
+    /// It is an error for no node to be a start.
+    pub fn start_positions<'a>(
+        &self,
+        network: &'a Network,
+    ) -> Result<Vec<(&'a str, usize)>, Error> {
+        let mut starts = network
+            .names()
+            .enumerate()
+            .filter(|(_position, name)| self.is_start(name))
+            .map(|(position, name)| (name, position))
+            .collect::<Vec<_>>();
+        if starts.is_empty() {
+            return Err(Error::NoSolution);
+        }
+        starts.sort_unstable();
+        Ok(starts)
+    }
+
+    /// Whether each position of the network is a goal.
+    ///
+    /// Matching patterns is comparatively slow, so walks should consult this instead of [`Self::is_goal`].
+    pub fn goal_mask(&self, network: &Network) -> Vec<bool> {
+        network.names().map(|name| self.is_goal(name)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("AAA", "AAA", true)]
+    #[case("AAA", "AAB", false)]
+    #[case("*A", "11A", true)]
+    #[case("*A", "A", true)]
+    #[case("*A", "AAB", false)]
+    #[case("?A", "11A", false)]
+    #[case("??A", "11A", true)]
+    #[case("1*", "11Z", true)]
+    #[case("*", "", true)]
+    #[case("", "", true)]
+    #[case("", "A", false)]
+    #[case("A*B*C", "AxxBxxBxxC", true)]
+    #[case("A*B*C", "AxxBxxC1", false)]
+    #[case("*B?", "BBBB", true)]
+    #[case("**Z", "ZZZ", true)]
+    fn glob(#[case] pattern: &str, #[case] name: &str, #[case] expect: bool) {
+        let pattern = pattern.parse::<NodePattern>().unwrap();
+        assert_eq!(pattern.matches(name), expect);
+    }
+
+    #[test]
+    fn overrides() {
+        let endpoints = Endpoints::part2().with_overrides(&[Endpoints::pattern("11A")], &[]);
+        assert!(endpoints.is_start("11A"));
+        assert!(!endpoints.is_start("22A"));
+        assert!(endpoints.is_goal("22Z"));
+    }
+}
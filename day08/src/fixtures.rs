@@ -0,0 +1,67 @@
+//! Networks and helpers shared between test modules.
+
+use crate::{input::NodeDefinition, models::Network, Error};
+
+/// The part 2 example from the puzzle.
+pub(crate) const PT2_EXAMPLE: &str = "11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+/// With directions `L`, `PPA` is on a goal at steps 2, 5, 8, 11, ... and `QQA` at steps 3, 7, 11, ...
+///
+/// The LCM of their first hits is 6, but the first common hit is 11.
+pub(crate) const LCM_WOULD_BE_WRONG: &str = "PPA = (PPB, PPB)
+PPB = (PPZ, PPZ)
+PPZ = (PPC, PPC)
+PPC = (PPB, PPB)
+QQA = (QQB, QQB)
+QQB = (QQC, QQC)
+QQC = (QQZ, QQZ)
+QQZ = (QQD, QQD)
+QQD = (QQB, QQB)";
+
+/// With directions `L`, `PPA` is on a goal at odd steps, and `QQA` at steps which are 2 mod 4.
+pub(crate) const NEVER_SIMULTANEOUS: &str = "PPA = (PPZ, PPZ)
+PPZ = (PPB, PPB)
+PPB = (PPZ, PPZ)
+QQA = (QQB, QQB)
+QQB = (QQZ, QQZ)
+QQZ = (QQC, QQC)
+QQC = (QQD, QQD)
+QQD = (QQB, QQB)";
+
+/// Parse one node definition per line.
+pub(crate) fn try_network(definitions: &str) -> Result<Network, Error> {
+    definitions
+        .lines()
+        .map(|line| line.parse::<NodeDefinition>().unwrap())
+        .collect::<Vec<_>>()
+        .try_into()
+}
+
+pub(crate) fn network(definitions: &str) -> Network {
+    try_network(definitions).unwrap()
+}
+
+/// The positions of every node whose name ends in `A`, ordered by name.
+pub(crate) fn ghost_starts(network: &Network) -> Vec<usize> {
+    let mut starts = network
+        .names()
+        .filter(|name| name.ends_with('A'))
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts
+        .into_iter()
+        .map(|name| network.position_of(name).unwrap())
+        .collect()
+}
+
+/// `true` for the positions of nodes whose names end in `Z`.
+pub(crate) fn is_ghost_goal(network: &Network) -> impl '_ + Fn(usize) -> bool {
+    |position| network.name_of(position).unwrap().ends_with('Z')
+}
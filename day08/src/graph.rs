@@ -0,0 +1,447 @@
+use std::{collections::VecDeque, fmt::Write as _, path::Path};
+
+use crate::{
+    cycles::Cycle,
+    endpoints::Endpoints,
+    input,
+    models::{Direction, Network},
+    Error,
+};
+
+/// Render the network in Graphviz DOT format.
+///
+/// Nodes are labelled with their names, and edges with the direction which follows them.
+/// When both directions lead to the same node, there is a single edge labelled `LR`.
+pub fn to_dot(network: &Network) -> String {
+    let mut dot = String::from("digraph network {\n");
+    for position in 0..network.len() {
+        let name = network
+            .name_of(position)
+            .expect("all valid positions have names");
+        // names may contain anything but separators, so quote them as DOT strings
+        let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(dot, "    n{position} [label=\"{name}\"];").expect("writing to a string succeeds");
+    }
+    for position in 0..network.len() {
+        let [left, right] = network.successors(position);
+        if left == right {
+            writeln!(dot, "    n{position} -> n{left} [label=\"LR\"];")
+        } else {
+            writeln!(dot, "    n{position} -> n{left} [label=\"L\"];")
+                .and_then(|()| writeln!(dot, "    n{position} -> n{right} [label=\"R\"];"))
+        }
+        .expect("writing to a string succeeds");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Find the strongly connected components of the network, using Tarjan's algorithm.
+///
+/// Components are produced in reverse topological order: no component has an edge to a later one.
+/// Each component's members are sorted by position.
+pub fn strongly_connected_components(network: &Network) -> Vec<Vec<usize>> {
+    // this is the iterative form of Tarjan's algorithm, so that deep networks can't overflow the stack
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; network.len()];
+    let mut low_link = vec![0; network.len()];
+    let mut on_stack = vec![false; network.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    // each frame is a node and the index of its next successor to visit
+    let mut call_stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 0..network.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        call_stack.push((root, 0));
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((node, successor_idx)) = call_stack.last_mut() {
+            let node = *node;
+            if let Some(&successor) = network.successors(node).get(*successor_idx) {
+                *successor_idx += 1;
+                if index[successor] == UNVISITED {
+                    index[successor] = next_index;
+                    low_link[successor] = next_index;
+                    next_index += 1;
+                    stack.push(successor);
+                    on_stack[successor] = true;
+                    call_stack.push((successor, 0));
+                } else if on_stack[successor] {
+                    low_link[node] = low_link[node].min(index[successor]);
+                }
+                continue;
+            }
+
+            // all successors visited
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                low_link[parent] = low_link[parent].min(low_link[node]);
+            }
+            if low_link[node] == index[node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().expect("node is on the stack");
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Find every node reachable from any of the `starts`, ignoring the instructions.
+///
+/// Each start is reachable from itself.
+pub fn reachable_from(network: &Network, starts: impl IntoIterator<Item = usize>) -> Vec<bool> {
+    let mut reachable = vec![false; network.len()];
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !reachable[start] {
+            reachable[start] = true;
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        for successor in network.successors(node) {
+            if !reachable[successor] {
+                reachable[successor] = true;
+                queue.push_back(successor);
+            }
+        }
+    }
+    reachable
+}
+
+/// Shortest routes from a single start to every node, ignoring the instructions.
+pub struct ShortestPaths {
+    /// For each node reached, the node and direction from which it was first reached.
+    ///
+    /// The start itself has no predecessor.
+    predecessors: Vec<Option<(usize, Direction)>>,
+    distances: Vec<Option<usize>>,
+}
+
+impl ShortestPaths {
+    /// Breadth-first search from `start`.
+    pub fn new(network: &Network, start: usize) -> Self {
+        let mut predecessors = vec![None; network.len()];
+        let mut distances = vec![None; network.len()];
+        let mut queue = VecDeque::new();
+        distances[start] = Some(0);
+        queue.push_back((start, 0));
+        while let Some((node, distance)) = queue.pop_front() {
+            for direction in [Direction::Left, Direction::Right] {
+                let successor = network.step(node, direction);
+                if distances[successor].is_none() {
+                    distances[successor] = Some(distance + 1);
+                    predecessors[successor] = Some((node, direction));
+                    queue.push_back((successor, distance + 1));
+                }
+            }
+        }
+        Self {
+            predecessors,
+            distances,
+        }
+    }
+
+    /// The fewest steps needed to reach `target`, if it can be reached at all.
+    pub fn distance(&self, target: usize) -> Option<usize> {
+        self.distances[target]
+    }
+
+    /// A shortest sequence of directions leading to `target`, if it can be reached at all.
+    pub fn route(&self, target: usize) -> Option<Vec<Direction>> {
+        let mut route = Vec::with_capacity(self.distance(target)?);
+        let mut node = target;
+        while let Some((predecessor, direction)) = self.predecessors[node] {
+            route.push(direction);
+            node = predecessor;
+        }
+        route.reverse();
+        Some(route)
+    }
+}
+
+/// Compare, for each start and each goal it can reach, the shortest route with the route the instructions take.
+pub fn compare_routes(input: &Path, endpoints: &Endpoints) -> Result<(), Error> {
+    let (directions, network) = input::parse(input)?;
+    let is_goal = endpoints.goal_mask(&network);
+    let mut goals = network
+        .names()
+        .enumerate()
+        .filter(|&(position, _name)| is_goal[position])
+        .map(|(position, name)| (name, position))
+        .collect::<Vec<_>>();
+    goals.sort_unstable();
+
+    for (name, start) in endpoints.start_positions(&network)? {
+        println!("from {name}:");
+        let shortest = ShortestPaths::new(&network, start);
+        let mut any_reachable = false;
+        for &(goal_name, goal) in &goals {
+            let Some(route) = shortest.route(goal) else {
+                continue;
+            };
+            any_reachable = true;
+            let route_str = route.iter().map(ToString::to_string).collect::<String>();
+
+            let following =
+                Cycle::detect(&network, &directions, start, |position| position == goal)
+                    .first_hit()
+                    .map_or_else(
+                        || "never".to_string(),
+                        |steps| format!("{steps} (+{})", steps - route.len() as u64),
+                    );
+            println!(
+                "  {goal_name}: shortest {} ({route_str}); following instructions {following}",
+                route.len()
+            );
+        }
+        if !any_reachable {
+            println!("  no goals reachable");
+        }
+    }
+    Ok(())
+}
+
+fn sorted_names(network: &Network, positions: impl Iterator<Item = usize>) -> Vec<&str> {
+    let mut names = positions
+        .map(|position| {
+            network
+                .name_of(position)
+                .expect("all valid positions have names")
+        })
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names
+}
+
+/// Print the network's structure: its cyclic components, which nodes no start can reach,
+/// and which goals each start can ever reach.
+pub fn analyze(
+    network: &Network,
+    is_start: impl Fn(&str) -> bool,
+    is_goal: impl Fn(&str) -> bool,
+) -> Result<(), Error> {
+    let name_of = |position| {
+        network
+            .name_of(position)
+            .expect("all valid positions have names")
+    };
+    let starts = (0..network.len())
+        .filter(|&position| is_start(name_of(position)))
+        .collect::<Vec<_>>();
+    if starts.is_empty() {
+        return Err(Error::NoSolution);
+    }
+
+    let components = strongly_connected_components(network);
+    // a component is only cyclic if it has several members or a self-loop
+    let cyclic = components
+        .iter()
+        .filter(|component| {
+            component.len() > 1 || network.successors(component[0]).contains(&component[0])
+        })
+        .collect::<Vec<_>>();
+    println!(
+        "{} nodes in {} strongly connected components, of which {} are cyclic",
+        network.len(),
+        components.len(),
+        cyclic.len()
+    );
+    for component in cyclic {
+        let names = sorted_names(network, component.iter().copied());
+        println!("  cycle of {}: {}", names.len(), names.join(" "));
+    }
+
+    let reachable = reachable_from(network, starts.iter().copied());
+    let unreachable = sorted_names(
+        network,
+        (0..network.len()).filter(|&position| !reachable[position]),
+    );
+    println!(
+        "{} nodes unreachable from any start: {}",
+        unreachable.len(),
+        unreachable.join(" ")
+    );
+
+    for &start in &starts {
+        let reachable = reachable_from(network, [start]);
+        let goals = sorted_names(
+            network,
+            (0..network.len())
+                .filter(|&position| reachable[position] && is_goal(name_of(position))),
+        );
+        println!("{} can reach goals: {}", name_of(start), goals.join(" "));
+    }
+
+    Ok(())
+}
+
+/// Analyze the network from the input file, with the given starts and goals.
+pub fn analyze_network(input: &Path, endpoints: &Endpoints) -> Result<(), Error> {
+    let (_directions, network) = input::parse(input)?;
+    analyze(
+        &network,
+        |name| endpoints.is_start(name),
+        |name| endpoints.is_goal(name),
+    )
+}
+
+/// Write the network in Graphviz DOT format.
+pub fn export_dot(input: &Path, output: &Path) -> Result<(), Error> {
+    let (_directions, network) = input::parse(input)?;
+    std::fs::write(output, to_dot(&network))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::network;
+
+    fn example() -> Network {
+        network(
+            "AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+XXX = (AAA, YYY)
+YYY = (XXX, XXX)",
+        )
+    }
+
+    fn names(network: &Network, components: &[Vec<usize>]) -> Vec<Vec<String>> {
+        let mut names = components
+            .iter()
+            .map(|component| {
+                let mut names = component
+                    .iter()
+                    .map(|&position| network.name_of(position).unwrap().to_string())
+                    .collect::<Vec<_>>();
+                names.sort_unstable();
+                names
+            })
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn dot_export() {
+        let network = network(
+            "AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)",
+        );
+        let dot = to_dot(&network);
+        let a = network.position_of("AAA").unwrap();
+        let b = network.position_of("BBB").unwrap();
+        let z = network.position_of("ZZZ").unwrap();
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains(&format!("n{a} [label=\"AAA\"];")));
+        assert!(dot.contains(&format!("n{a} -> n{b} [label=\"LR\"];")));
+        assert!(dot.contains(&format!("n{b} -> n{a} [label=\"L\"];")));
+        assert!(dot.contains(&format!("n{b} -> n{z} [label=\"R\"];")));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn dot_escapes_names() {
+        let network = network(
+            r#"say "hi" = (back\slash, back\slash)
+back\slash = (say "hi", say "hi")"#,
+        );
+        let dot = to_dot(&network);
+        assert!(dot.contains(r#"[label="say \"hi\""];"#));
+        assert!(dot.contains(r#"[label="back\\slash"];"#));
+    }
+
+    #[test]
+    fn components() {
+        let network = example();
+        let components = strongly_connected_components(&network);
+        assert_eq!(
+            names(&network, &components),
+            vec![
+                vec!["AAA"],
+                vec!["BBB"],
+                vec!["CCC"],
+                vec!["DDD"],
+                vec!["EEE"],
+                vec!["GGG"],
+                vec!["XXX", "YYY"],
+                vec!["ZZZ"],
+            ]
+        );
+
+        // reverse topological order: every edge leads to the same or an earlier component
+        let component_of = |position| {
+            components
+                .iter()
+                .position(|component| component.contains(&position))
+                .unwrap()
+        };
+        for position in 0..network.len() {
+            for successor in network.successors(position) {
+                assert!(component_of(successor) <= component_of(position));
+            }
+        }
+    }
+
+    #[test]
+    fn reachability() {
+        let network = example();
+        let reachable = reachable_from(&network, [network.position_of("CCC").unwrap()]);
+        let reachable = sorted_names(
+            &network,
+            (0..network.len()).filter(|&position| reachable[position]),
+        );
+        assert_eq!(reachable, vec!["CCC", "GGG", "ZZZ"]);
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let network = example();
+        let position = |name| network.position_of(name).unwrap();
+        let shortest = ShortestPaths::new(&network, position("XXX"));
+
+        assert_eq!(shortest.distance(position("XXX")), Some(0));
+        assert_eq!(shortest.route(position("XXX")), Some(Vec::new()));
+        assert_eq!(shortest.distance(position("ZZZ")), Some(3));
+        assert_eq!(
+            shortest.route(position("ZZZ")),
+            Some(vec![Direction::Left, Direction::Right, Direction::Left])
+        );
+        assert_eq!(
+            shortest.route(position("YYY")),
+            Some(vec![Direction::Right])
+        );
+        assert_eq!(shortest.distance(position("DDD")), Some(3));
+
+        let shortest = ShortestPaths::new(&network, position("AAA"));
+        assert_eq!(shortest.distance(position("XXX")), None);
+        assert_eq!(shortest.route(position("YYY")), None);
+    }
+}
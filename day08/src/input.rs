@@ -1,7 +1,7 @@
 use aoclib::input::parse_two_phase;
 
 use crate::{
-    models::{Direction, Network},
+    models::{Direction, Network, NodeName},
     Error,
 };
 use std::{path::Path, str::FromStr};
@@ -22,6 +22,9 @@ impl FromStr for Directions {
             })?;
             directions.push(d);
         }
+        if directions.is_empty() {
+            return Err(Error::Parse("no directions".into()));
+        }
         Ok(Self(directions))
     }
 }
@@ -29,9 +32,9 @@ impl FromStr for Directions {
 #[derive(Debug, parse_display::Display, parse_display::FromStr)]
 #[display("{name} = ({left}, {right})")]
 pub struct NodeDefinition {
-    pub name: String,
-    pub left: String,
-    pub right: String,
+    pub name: NodeName,
+    pub left: NodeName,
+    pub right: NodeName,
 }
 
 #[derive(Default)]
@@ -0,0 +1,239 @@
+use crate::models::{Direction, Network};
+
+/// Precomputed jumps through the network, a whole pass over the directions at a time.
+///
+/// A pass is `directions.len()` steps beginning at instruction 0. Because every pass
+/// follows the same instructions, where it ends and which of its steps land on a goal
+/// depend only on the node it starts from. On top of the single-pass table we build
+/// binary-lifting tables, so that `2^k` passes can be taken in a single lookup.
+pub struct JumpTable<'a> {
+    network: &'a Network,
+    directions: &'a [Direction],
+    /// `passes[k][node]`: the node reached after `2^k` passes from `node`.
+    passes: Vec<Vec<usize>>,
+    /// `hit_within[k][node]`: whether any step of those `2^k` passes is on a goal.
+    hit_within: Vec<Vec<bool>>,
+    /// `pass_hits[node]`: sorted offsets into a pass from `node` at which the walk is on a goal.
+    ///
+    /// Offset 0 is the start node itself; the node at the end of the pass belongs to the next pass.
+    pass_hits: Vec<Vec<u64>>,
+}
+
+impl<'a> JumpTable<'a> {
+    pub fn new(
+        network: &'a Network,
+        directions: &'a [Direction],
+        is_goal: impl Fn(usize) -> bool,
+    ) -> Self {
+        debug_assert!(!directions.is_empty(), "parsing rejects empty directions");
+
+        let mut pass_ends = Vec::with_capacity(network.len());
+        let mut pass_hits = Vec::with_capacity(network.len());
+        for start in 0..network.len() {
+            let mut position = start;
+            let mut hits = Vec::new();
+            for (offset, &direction) in directions.iter().enumerate() {
+                if is_goal(position) {
+                    hits.push(offset as u64);
+                }
+                position = network.step(position, direction);
+            }
+            pass_ends.push(position);
+            pass_hits.push(hits);
+        }
+
+        // enough levels that the largest jump covers every pass which fits in a `u64` step count
+        let n_levels = (u64::MAX / directions.len() as u64).ilog2() as usize + 1;
+        let mut passes = Vec::with_capacity(n_levels);
+        let mut hit_within: Vec<Vec<bool>> = Vec::with_capacity(n_levels);
+        passes.push(pass_ends);
+        hit_within.push(pass_hits.iter().map(|hits| !hits.is_empty()).collect());
+        for level in 1..n_levels {
+            let previous = &passes[level - 1];
+            let previous_hits = &hit_within[level - 1];
+            let (next, next_hits) = (0..network.len())
+                .map(|node| {
+                    let middle = previous[node];
+                    (
+                        previous[middle],
+                        previous_hits[node] || previous_hits[middle],
+                    )
+                })
+                .unzip();
+            passes.push(next);
+            hit_within.push(next_hits);
+        }
+
+        Self {
+            network,
+            directions,
+            passes,
+            hit_within,
+            pass_hits,
+        }
+    }
+
+    fn pass_length(&self) -> u64 {
+        self.directions.len() as u64
+    }
+
+    /// The node reached after `n_passes` whole passes from `node`.
+    pub fn jump_passes(&self, mut node: usize, n_passes: u64) -> usize {
+        for (level, table) in self.passes.iter().enumerate() {
+            if n_passes >> level & 1 == 1 {
+                node = table[node];
+            }
+        }
+        node
+    }
+
+    /// The node reached after `steps` steps from `start`.
+    ///
+    /// Takes `O(log steps + directions.len())` time, independent of how many steps there are.
+    pub fn position_after(&self, start: usize, steps: u64) -> usize {
+        let node = self.jump_passes(start, steps / self.pass_length());
+        self.directions[..(steps % self.pass_length()) as usize]
+            .iter()
+            .fold(node, |node, &direction| self.network.step(node, direction))
+    }
+
+    /// Starting at `node` at the beginning of pass number `pass`, find the first pass at or after it which includes a goal.
+    ///
+    /// Returns that pass's number and the node it starts from, or `None` if the walk never reaches a goal again.
+    fn next_hit_pass(&self, mut node: usize, mut pass: u64) -> Option<(u64, usize)> {
+        for level in (0..self.passes.len()).rev() {
+            if self.hit_within[level][node] {
+                continue;
+            }
+            if let Some(next_pass) = pass.checked_add(1 << level) {
+                node = self.passes[level][node];
+                pass = next_pass;
+            }
+        }
+        (!self.pass_hits[node].is_empty()).then_some((pass, node))
+    }
+
+    /// Simulate every ghost together, and find the first step at which they are all on a goal.
+    ///
+    /// Each ghost skips directly to its next pass which contains a goal, so the simulation
+    /// only ever examines passes in which some ghost could be on a goal. Returns `None` if
+    /// the ghosts are not all on a goal at the same time within `limit` steps.
+    pub fn first_common_goal(&self, starts: &[usize], limit: u64) -> Option<u64> {
+        if starts.is_empty() {
+            return None;
+        }
+
+        // each ghost's `(pass, node)`: the beginning of the pass it is currently considering
+        let mut ghosts = starts.iter().map(|&start| (0, start)).collect::<Vec<_>>();
+        let mut target = 0_u64;
+
+        loop {
+            if target.checked_mul(self.pass_length())? > limit {
+                return None;
+            }
+
+            let mut agreed = true;
+            for ghost in &mut ghosts {
+                let (pass, node) = *ghost;
+                let node = self.jump_passes(node, target - pass.min(target));
+                *ghost = self.next_hit_pass(node, pass.max(target))?;
+                if ghost.0 > target {
+                    target = ghost.0;
+                    agreed = false;
+                }
+            }
+            if !agreed {
+                continue;
+            }
+
+            // every ghost reaches a goal somewhere during pass `target`; do they do so together?
+            let (first, rest) = ghosts.split_first().expect("starts is not empty");
+            let common_offset = self.pass_hits[first.1].iter().copied().find(|offset| {
+                rest.iter()
+                    .all(|&(_pass, node)| self.pass_hits[node].binary_search(offset).is_ok())
+            });
+            if let Some(offset) = common_offset {
+                // past `u64::MAX` steps is certainly past the limit
+                let steps = target
+                    .checked_mul(self.pass_length())?
+                    .checked_add(offset)?;
+                return (steps <= limit).then_some(steps);
+            }
+            target = target.checked_add(1)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{
+        ghost_starts, is_ghost_goal, network, LCM_WOULD_BE_WRONG, NEVER_SIMULTANEOUS, PT2_EXAMPLE,
+    };
+
+    #[test]
+    fn position_after_agrees_with_stepping() {
+        let network = network(PT2_EXAMPLE);
+        let directions = [Direction::Left, Direction::Right, Direction::Right];
+        let table = JumpTable::new(&network, &directions, is_ghost_goal(&network));
+        for start in 0..network.len() {
+            let mut position = start;
+            for steps in 0..50 {
+                assert_eq!(table.position_after(start, steps), position);
+                position = network.step(position, directions[steps as usize % directions.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn simulates_pt2_example() {
+        let network = network(PT2_EXAMPLE);
+        let directions = [Direction::Left, Direction::Right];
+        let table = JumpTable::new(&network, &directions, is_ghost_goal(&network));
+        assert_eq!(
+            table.first_common_goal(&ghost_starts(&network), u64::MAX),
+            Some(6)
+        );
+        assert_eq!(table.first_common_goal(&ghost_starts(&network), 5), None);
+    }
+
+    #[test]
+    fn simulates_past_first_hits() {
+        let network = network(LCM_WOULD_BE_WRONG);
+        let directions = [Direction::Left];
+        let table = JumpTable::new(&network, &directions, is_ghost_goal(&network));
+        assert_eq!(
+            table.first_common_goal(&ghost_starts(&network), u64::MAX),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn never_simultaneous() {
+        let network = network(NEVER_SIMULTANEOUS);
+        let directions = [Direction::Left];
+        let table = JumpTable::new(&network, &directions, is_ghost_goal(&network));
+        assert_eq!(
+            table.first_common_goal(&ghost_starts(&network), 100_000),
+            None
+        );
+    }
+
+    #[test]
+    fn ghost_which_stops_reaching_goals() {
+        let network = network(
+            "PPA = (PPZ, PPZ)
+PPZ = (PPC, PPC)
+PPC = (PPC, PPC)
+QQA = (QQB, QQB)
+QQB = (QQZ, QQZ)
+QQZ = (QQB, QQB)",
+        );
+        let directions = [Direction::Left];
+        let table = JumpTable::new(&network, &directions, is_ghost_goal(&network));
+        assert_eq!(
+            table.first_common_goal(&ghost_starts(&network), u64::MAX),
+            None
+        );
+    }
+}
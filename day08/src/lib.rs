@@ -3,11 +3,22 @@ use std::{
     path::Path,
 };
 
+use cycles::Cycle;
+pub use diagnostics::diagnose;
+use endpoints::Endpoints;
+use jump::JumpTable;
 use models::{Direction, Network};
 
-use crate::models::{directions_iter, INITIAL_NAME, TARGET_NAME};
+use crate::models::directions_iter;
 
+pub mod cycles;
+mod diagnostics;
+pub mod endpoints;
+#[cfg(test)]
+mod fixtures;
+pub mod graph;
 pub mod input;
+pub mod jump;
 pub mod models;
 
 fn steps_for(
@@ -15,32 +26,44 @@ fn steps_for(
     position: &mut usize,
     directions: &[Direction],
     is_finished: impl Fn(usize) -> bool,
-) -> usize {
-    let mut total = None;
+) -> Option<usize> {
+    // once every `(position, instruction)` state has been visited, the walk can only repeat itself
+    let max_steps = network.len() * directions.len();
 
-    for (steps, direction) in directions_iter(directions).enumerate() {
+    for (steps, direction) in directions_iter(directions).enumerate().take(max_steps + 1) {
         if is_finished(*position) {
-            total = Some(steps);
-            break;
+            return Some(steps);
         }
 
         *position = network.step(*position, direction);
     }
 
-    total
-        .expect("as directions is an infinite iterator, we are only here if we've assigned a total")
+    None
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path, endpoints: &Endpoints) -> Result<(), Error> {
     let (directions, network) = input::parse(input)?;
-    let target = network.position_of(TARGET_NAME).ok_or(Error::NoSolution)?;
-    let mut position = network.position_of(INITIAL_NAME).ok_or(Error::NoSolution)?;
+    let is_goal = endpoints.goal_mask(&network);
+    let starts = endpoints.start_positions(&network)?;
 
-    let total_steps = steps_for(&network, &mut position, &directions, |position| {
-        position == target
-    });
+    let mut any_solution = false;
+    for &(name, mut position) in &starts {
+        let total_steps = steps_for(&network, &mut position, &directions, |position| {
+            is_goal[position]
+        });
+        any_solution |= total_steps.is_some();
+        let total_steps =
+            total_steps.map_or_else(|| "never".to_string(), |steps| steps.to_string());
+        if starts.len() == 1 {
+            println!("total steps (pt 1): {total_steps}");
+        } else {
+            println!("total steps (pt 1) from {name}: {total_steps}");
+        }
+    }
 
-    println!("total steps (pt 1): {total_steps}");
+    if !any_solution {
+        return Err(Error::NoSolution);
+    }
     Ok(())
 }
 
@@ -87,7 +110,51 @@ where
     ts.iter().copied().reduce(lcm2).unwrap_or_default()
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+/// Extended Euclidean Algorithm
+///
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// Generalized Chinese Remainder Theorem for two congruences.
+///
+/// Finds `x` such that `x ≡ a1 (mod m1)` and `x ≡ a2 (mod m2)`; the moduli need not be coprime.
+/// Returns `(x, lcm(m1, m2))` with `x` in `0..lcm(m1, m2)`, or `None` if the congruences are inconsistent.
+fn crt2(a1: i128, m1: i128, a2: i128, m2: i128) -> Option<(i128, i128)> {
+    let (g, p, _q) = extended_gcd(m1, m2);
+    if (a2 - a1) % g != 0 {
+        return None;
+    }
+    let modulus = m1 / g * m2;
+    let k = ((a2 - a1) / g * p).rem_euclid(m2 / g);
+    let x = (a1 + m1 * k).rem_euclid(modulus);
+    Some((x, modulus))
+}
+
+/// Detect the cycle of each ghost, in order of their start nodes' names.
+fn ghost_cycles<'a>(
+    network: &'a Network,
+    directions: &[Direction],
+    endpoints: &Endpoints,
+) -> Result<Vec<(&'a str, Cycle)>, Error> {
+    let is_goal = endpoints.goal_mask(network);
+    Ok(endpoints
+        .start_positions(network)?
+        .into_iter()
+        .map(|(name, start)| {
+            let cycle = Cycle::detect(network, directions, start, |position| is_goal[position]);
+            (name, cycle)
+        })
+        .collect())
+}
+
+pub fn part2(input: &Path, endpoints: &Endpoints) -> Result<(), Error> {
     // this feels like an occasion for chinese remainder theorem, because it rarely fails to show up in AoC at some point,
     // but it seems non-obvious that this will actually work.
     //
@@ -95,39 +162,141 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     //
     // [edit] yeah, 3 hours wasn't enough, and I'm not going to let it do more than that. Saw by accident that LCM
     // works out pretty well, which is nice, becasue CRT is complicated. Let's try it on my input though.
+    //
+    // [edit 2] LCM only works because each ghost's first goal happens to be exactly one cycle length in.
+    // We now detect each ghost's cycle explicitly, and only use LCM when that property provably holds;
+    // otherwise, CRT it is.
 
     let (directions, network) = input::parse(input)?;
-    let mut positions = network
-        .names()
-        // v-- this line is _very important_! do not omit!
-        .filter(|name| name.ends_with('A'))
-        .map(|name| network.position_of(name).ok_or(Error::NoSolution))
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let steps = positions
-        .iter_mut()
-        .map(|position| {
-            steps_for(&network, position, &directions, |position| {
-                network
-                    .name_of(position)
-                    .expect("all valid positions have names")
-                    .ends_with('Z')
-            }) as u128
-        })
+    let cycles = ghost_cycles(&network, &directions, endpoints)?
+        .into_iter()
+        .map(|(_name, cycle)| cycle)
         .collect::<Vec<_>>();
 
-    let total_steps = lcm(&steps);
+    let total_steps = cycles::first_common_goal(&cycles).ok_or(Error::NoSolution)?;
 
     println!("total steps (pt 2): {total_steps}");
     Ok(())
 }
 
+/// Cross-check part 2 by simulating every ghost together, using jump tables to skip ahead.
+///
+/// Confirms that every ghost is on a goal at the step found by cycle analysis, and that
+/// no earlier step within `limit` has them all on goals. When the answer exceeds `limit`,
+/// it is only partially verified.
+pub fn verify_part2(input: &Path, endpoints: &Endpoints, limit: u64) -> Result<(), Error> {
+    let (directions, network) = input::parse(input)?;
+    let goal_mask = endpoints.goal_mask(&network);
+    let is_goal = |position: usize| goal_mask[position];
+    let ghosts = ghost_cycles(&network, &directions, endpoints)?;
+    let starts = endpoints
+        .start_positions(&network)?
+        .into_iter()
+        .map(|(_name, start)| start)
+        .collect::<Vec<_>>();
+    let cycles = ghosts
+        .into_iter()
+        .map(|(_name, cycle)| cycle)
+        .collect::<Vec<_>>();
+    let by_cycles = cycles::first_common_goal(&cycles);
+
+    let table = JumpTable::new(&network, &directions, is_goal);
+    match verify_with_table(&table, &starts, is_goal, by_cycles, limit)? {
+        Verification::Simulated(steps) => println!("verified total steps (pt 2): {steps}"),
+        Verification::NoneEarlier {
+            steps,
+            search_limit,
+            on_goals_checked: true,
+        } => println!(
+            "all ghosts are on goals at step {steps}, and at no earlier step up to {search_limit}"
+        ),
+        Verification::NoneEarlier {
+            steps,
+            search_limit,
+            on_goals_checked: false,
+        } => println!(
+            "step {steps} is beyond the reach of the jump table; no earlier common goal up to {search_limit}"
+        ),
+        Verification::NoneWithin(search_limit) => {
+            println!("no common goal within {search_limit} steps")
+        }
+    }
+    Ok(())
+}
+
+/// How much of the cycle analysis's answer simulation could confirm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verification {
+    /// Simulation found the same first common goal.
+    Simulated(u64),
+    /// Simulation found no common goal up to `search_limit`, which is short of the answer `steps`.
+    ///
+    /// `on_goals_checked` is `false` when `steps` is too large for the jump table to reach,
+    /// so that it is unknown whether every ghost is on a goal there.
+    NoneEarlier {
+        steps: u128,
+        search_limit: u64,
+        on_goals_checked: bool,
+    },
+    /// Neither cycle analysis nor simulation found a common goal, up to `search_limit`.
+    NoneWithin(u64),
+}
+
+fn verify_with_table(
+    table: &JumpTable,
+    starts: &[usize],
+    is_goal: impl Fn(usize) -> bool,
+    by_cycles: Option<u128>,
+    limit: u64,
+) -> Result<Verification, Error> {
+    // `None` when the answer is out of the jump table's range
+    let reachable = by_cycles.and_then(|steps| u64::try_from(steps).ok());
+    if let Some(steps) = reachable {
+        let all_on_goals = starts
+            .iter()
+            .all(|&start| is_goal(table.position_after(start, steps)));
+        if !all_on_goals {
+            return Err(Error::NotAllOnGoals {
+                steps: steps.into(),
+            });
+        }
+    }
+
+    let search_limit = reachable.map_or(limit, |steps| steps.min(limit));
+    let simulated = table.first_common_goal(starts, search_limit);
+    if simulated.map(u128::from) != by_cycles.filter(|&steps| steps <= search_limit as u128) {
+        return Err(Error::VerificationFailed {
+            by_cycles,
+            simulated,
+        });
+    }
+
+    Ok(match (by_cycles, simulated) {
+        (_, Some(steps)) => Verification::Simulated(steps),
+        (Some(steps), None) => Verification::NoneEarlier {
+            steps,
+            search_limit,
+            on_goals_checked: reachable.is_some(),
+        },
+        (None, None) => Verification::NoneWithin(search_limit),
+    })
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error("Parse error: {0}")]
     Parse(String),
+    #[error("ghosts are not all on goals at step {steps}, contrary to cycle analysis")]
+    NotAllOnGoals { steps: u128 },
+    #[error(
+        "cycle analysis found first common goal {by_cycles:?}, but simulation found {simulated:?}"
+    )]
+    VerificationFailed {
+        by_cycles: Option<u128>,
+        simulated: Option<u64>,
+    },
     #[error("no solution found")]
     NoSolution,
 }
@@ -147,4 +316,65 @@ mod tests {
     fn test_lcm(#[case] ts: &[u32], #[case] expect: u32) {
         assert_eq!(lcm(ts), expect);
     }
+
+    #[rstest]
+    #[case(2, 3, 3, 5, Some((8, 15)))]
+    #[case(0, 4, 2, 6, Some((8, 12)))]
+    #[case(1, 4, 2, 6, None)]
+    #[case(1, 4, 3, 6, Some((9, 12)))]
+    #[case(2, 3, 3, 4, Some((11, 12)))]
+    #[case(0, 1, 5, 7, Some((5, 7)))]
+    fn test_crt2(
+        #[case] a1: i128,
+        #[case] m1: i128,
+        #[case] a2: i128,
+        #[case] m2: i128,
+        #[case] expect: Option<(i128, i128)>,
+    ) {
+        assert_eq!(crt2(a1, m1, a2, m2), expect);
+    }
+
+    #[test]
+    fn answer_beyond_jump_table() {
+        // one ghost per prime: a start, then a ring of that length whose goal is a whole lap in
+        const PRIMES: [u64; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let mut definitions = Vec::new();
+        for p in PRIMES {
+            let ring = |idx: u64| {
+                if idx == p {
+                    format!("{p}Z")
+                } else {
+                    format!("{p}-{idx}")
+                }
+            };
+            definitions.push(format!("{p}A = ({}, {})", ring(1), ring(1)));
+            for idx in 1..=p {
+                let next = ring(idx % p + 1);
+                definitions.push(format!("{} = ({next}, {next})", ring(idx)));
+            }
+        }
+        let network = fixtures::network(&definitions.join("\n"));
+        let directions = [Direction::Left];
+        let is_goal = fixtures::is_ghost_goal(&network);
+        let starts = fixtures::ghost_starts(&network);
+        let cycles = starts
+            .iter()
+            .map(|&start| Cycle::detect(&network, &directions, start, &is_goal))
+            .collect::<Vec<_>>();
+
+        let by_cycles = cycles::first_common_goal(&cycles);
+        let product = PRIMES.iter().map(|&p| u128::from(p)).product::<u128>();
+        assert_eq!(by_cycles, Some(product));
+        assert!(product > u128::from(u64::MAX));
+
+        let table = JumpTable::new(&network, &directions, &is_goal);
+        assert_eq!(
+            verify_with_table(&table, &starts, &is_goal, by_cycles, 10_000).unwrap(),
+            Verification::NoneEarlier {
+                steps: product,
+                search_limit: 10_000,
+                on_goals_checked: false,
+            }
+        );
+    }
 }
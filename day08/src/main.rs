@@ -1,5 +1,10 @@
 use aoclib::{config::Config, website::get_input};
-use day08::{part1, part2};
+use day08::{
+    diagnose,
+    endpoints::{Endpoints, NodePattern},
+    graph::{analyze_network, compare_routes, export_dot},
+    part1, part2, verify_part2,
+};
 
 use clap::Parser;
 use color_eyre::eyre::Result;
@@ -22,6 +27,42 @@ struct RunArgs {
     /// run part 2
     #[arg(long)]
     part2: bool,
+
+    /// start node name or glob pattern (`*`, `?`); may be repeated
+    ///
+    /// when unset, part 1 starts from `AAA` and part 2 from `*A`
+    #[arg(long = "start", value_name = "PATTERN")]
+    starts: Vec<NodePattern>,
+
+    /// goal node name or glob pattern (`*`, `?`); may be repeated
+    ///
+    /// when unset, part 1 aims for `ZZZ` and part 2 for `*Z`
+    #[arg(long = "goal", value_name = "PATTERN")]
+    goals: Vec<NodePattern>,
+
+    /// cross-check part 2 by simulating all ghosts together with jump tables
+    #[arg(long)]
+    verify: bool,
+
+    /// how many steps `--verify` may simulate before giving up
+    #[arg(long, default_value_t = 10_000_000_000)]
+    verify_limit: u64,
+
+    /// check the input against the assumptions behind the LCM shortcut
+    #[arg(long)]
+    diagnose: bool,
+
+    /// report the network's cycles, unreachable nodes, and the goals reachable from part 2's starts
+    #[arg(long)]
+    analyze: bool,
+
+    /// compare the shortest route from each of part 2's starts to each goal with the instructions' route
+    #[arg(long)]
+    shortest: bool,
+
+    /// write the network in Graphviz DOT format to this path
+    #[arg(long)]
+    dot: Option<PathBuf>,
 }
 
 impl RunArgs {
@@ -37,6 +78,10 @@ impl RunArgs {
             Some(ref path) => Ok(path.clone()),
         }
     }
+
+    fn endpoints(&self, defaults: Endpoints) -> Endpoints {
+        defaults.with_overrides(&self.starts, &self.goals)
+    }
 }
 
 fn main() -> Result<()> {
@@ -44,11 +89,29 @@ fn main() -> Result<()> {
     let args = RunArgs::parse();
     let input_path = args.input()?;
 
+    let part1_endpoints = args.endpoints(Endpoints::part1());
+    let part2_endpoints = args.endpoints(Endpoints::part2());
+
     if !args.no_part1 {
-        part1(&input_path)?;
+        part1(&input_path, &part1_endpoints)?;
     }
     if args.part2 {
-        part2(&input_path)?;
+        part2(&input_path, &part2_endpoints)?;
+    }
+    if args.verify {
+        verify_part2(&input_path, &part2_endpoints, args.verify_limit)?;
+    }
+    if args.diagnose {
+        diagnose(&input_path, &part2_endpoints)?;
+    }
+    if args.analyze {
+        analyze_network(&input_path, &part2_endpoints)?;
+    }
+    if args.shortest {
+        compare_routes(&input_path, &part2_endpoints)?;
+    }
+    if let Some(output) = &args.dot {
+        export_dot(&input_path, output)?;
     }
     Ok(())
 }
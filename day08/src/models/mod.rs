@@ -2,4 +2,6 @@ mod direction;
 pub use direction::{directions_iter, Direction};
 
 mod network;
+mod node_name;
 pub use network::{Network, INITIAL_NAME, TARGET_NAME};
+pub use node_name::{NodeCode, NodeName};
@@ -1,15 +1,23 @@
-use std::collections::HashMap;
-
 use crate::{input::NodeDefinition, Error};
 
-use super::Direction;
+use super::{
+    node_name::{NodeCode, CODE_SPACE},
+    Direction, NodeName,
+};
 
 pub const INITIAL_NAME: &str = "AAA";
 pub const TARGET_NAME: &str = "ZZZ";
 
+/// Marks an unused slot in `Network::code_positions`.
+const ABSENT: u32 = u32::MAX;
+
 pub struct Network {
-    names: HashMap<String, usize>,
-    name_for: HashMap<usize, String>,
+    /// Position of each node with a [`NodeCode`] name, indexed by the packed code.
+    code_positions: Vec<u32>,
+    /// Positions of all other nodes, sorted by name.
+    other_positions: Vec<usize>,
+    /// Name of each position.
+    names: Vec<NodeName>,
     left: Vec<usize>,
     right: Vec<usize>,
 }
@@ -18,48 +26,74 @@ impl TryFrom<Vec<NodeDefinition>> for Network {
     type Error = Error;
 
     fn try_from(value: Vec<NodeDefinition>) -> Result<Self, Error> {
-        let mut names = HashMap::with_capacity(value.len());
-        let mut name_for = HashMap::with_capacity(value.len());
+        let mut network = Self {
+            code_positions: Vec::new(),
+            other_positions: Vec::new(),
+            names: Vec::with_capacity(value.len()),
+            left: Vec::with_capacity(value.len()),
+            right: Vec::with_capacity(value.len()),
+        };
         let mut left_name = Vec::with_capacity(value.len());
         let mut right_name = Vec::with_capacity(value.len());
 
         for NodeDefinition { name, left, right } in value {
-            let idx = names.len();
-            let ejected = names.insert(name.clone(), idx);
-            if let Some(ejected) = ejected {
-                return Err(Error::Parse(format!(
-                    "duplicate node definition: {name} ({idx} & {ejected})"
-                )));
+            let idx = network.names.len();
+            match &name {
+                NodeName::Code(code) => {
+                    if network.code_positions.is_empty() {
+                        network.code_positions = vec![ABSENT; CODE_SPACE];
+                    }
+                    let slot = &mut network.code_positions[code.packed() as usize];
+                    if *slot != ABSENT {
+                        return Err(Error::Parse(format!(
+                            "duplicate node definition: {name} ({idx} & {slot})"
+                        )));
+                    }
+                    *slot = idx
+                        .try_into()
+                        .map_err(|_| Error::Parse("too many nodes".into()))?;
+                }
+                NodeName::Other(_) => network.other_positions.push(idx),
             }
-            name_for.insert(idx, name);
+            network.names.push(name);
             left_name.push(left);
             right_name.push(right);
         }
 
-        let left = left_name
+        let names = &network.names;
+        network
+            .other_positions
+            .sort_unstable_by_key(|&position| names[position].as_str());
+        if let Some(pair) = network
+            .other_positions
+            .windows(2)
+            .find(|pair| names[pair[0]] == names[pair[1]])
+        {
+            return Err(Error::Parse(format!(
+                "duplicate node definition: {} ({} & {})",
+                names[pair[0]], pair[0], pair[1]
+            )));
+        }
+
+        network.left = left_name
             .iter()
             .map(|left| {
-                names.get(left).copied().ok_or_else(|| {
+                network.position_of_name(left).ok_or_else(|| {
                     Error::Parse(format!("left name \"{left}\" not found in node names"))
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let right = right_name
+        network.right = right_name
             .iter()
             .map(|right| {
-                names.get(right).copied().ok_or_else(|| {
+                network.position_of_name(right).ok_or_else(|| {
                     Error::Parse(format!("right name \"{right}\" not found in node names"))
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(Self {
-            names,
-            name_for,
-            left,
-            right,
-        })
+        Ok(network)
     }
 }
 
@@ -71,15 +105,98 @@ impl Network {
         }
     }
 
+    /// The left and right successors of this position.
+    pub fn successors(&self, position: usize) -> [usize; 2] {
+        [self.left[position], self.right[position]]
+    }
+
+    fn position_of_code(&self, code: NodeCode) -> Option<usize> {
+        self.code_positions
+            .get(code.packed() as usize)
+            .filter(|&&position| position != ABSENT)
+            .map(|&position| position as usize)
+    }
+
+    fn position_of_other(&self, name: &str) -> Option<usize> {
+        self.other_positions
+            .binary_search_by_key(&name, |&position| self.names[position].as_str())
+            .ok()
+            .map(|idx| self.other_positions[idx])
+    }
+
+    fn position_of_name(&self, name: &NodeName) -> Option<usize> {
+        match name {
+            NodeName::Code(code) => self.position_of_code(*code),
+            NodeName::Other(name) => self.position_of_other(name),
+        }
+    }
+
     pub fn position_of(&self, name: &str) -> Option<usize> {
-        self.names.get(name).copied()
+        match NodeCode::new(name) {
+            Some(code) => self.position_of_code(code),
+            None => self.position_of_other(name),
+        }
     }
 
     pub fn name_of(&self, position: usize) -> Option<&str> {
-        self.name_for.get(&position).map(String::as_str)
+        self.names.get(position).map(NodeName::as_str)
     }
 
+    /// All node names, in position order.
     pub fn names(&self) -> impl Iterator<Item = &str> {
-        self.names.keys().map(String::as_str)
+        self.names.iter().map(NodeName::as_str)
+    }
+
+    /// The number of nodes in the network.
+    ///
+    /// Positions are always in `0..len()`.
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::try_network;
+
+    #[test]
+    fn mixed_names() {
+        let network = try_network(
+            "AAA = (start, ZZZ)
+start = (finish, AAA)
+finish = (ZZZ, start)
+ZZZ = (ZZZ, ZZZ)",
+        )
+        .unwrap();
+
+        for (position, name) in ["AAA", "start", "finish", "ZZZ"].into_iter().enumerate() {
+            assert_eq!(network.position_of(name), Some(position));
+            assert_eq!(network.name_of(position), Some(name));
+        }
+        assert_eq!(network.successors(0), [1, 3]);
+        assert_eq!(network.successors(1), [2, 0]);
+        assert_eq!(network.position_of("BBB"), None);
+        assert_eq!(network.position_of("middle"), None);
+        assert_eq!(network.name_of(4), None);
+    }
+
+    #[test]
+    fn duplicate_code() {
+        assert!(try_network("AAA = (AAA, AAA)\nAAA = (AAA, AAA)").is_err());
+    }
+
+    #[test]
+    fn duplicate_other() {
+        assert!(try_network("aaa = (aaa, aaa)\naaa = (aaa, aaa)").is_err());
+    }
+
+    #[test]
+    fn unknown_successor() {
+        assert!(try_network("AAA = (AAA, BBB)").is_err());
+        assert!(try_network("aaa = (bbb, aaa)").is_err());
     }
 }
@@ -0,0 +1,107 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The characters which may appear in a [`NodeCode`], in packing order.
+const CODE_ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// The number of distinct packed values a [`NodeCode`] can take.
+pub(crate) const CODE_SPACE: usize = CODE_ALPHABET.len().pow(3);
+
+fn digit(byte: u8) -> Option<u16> {
+    match byte {
+        b'0'..=b'9' => Some((byte - b'0') as u16),
+        b'A'..=b'Z' => Some((byte - b'A') as u16 + 10),
+        _ => None,
+    }
+}
+
+/// A node name in the puzzle's usual form: exactly three uppercase letters or digits.
+///
+/// These pack densely into an integer below [`CODE_SPACE`], which makes for a cheap lookup key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeCode([u8; 3]);
+
+impl NodeCode {
+    pub fn new(name: &str) -> Option<Self> {
+        let bytes: [u8; 3] = name.as_bytes().try_into().ok()?;
+        bytes
+            .iter()
+            .all(|&byte| digit(byte).is_some())
+            .then_some(Self(bytes))
+    }
+
+    /// This code as a base-36 integer.
+    pub fn packed(self) -> u16 {
+        self.0.iter().fold(0, |packed, &byte| {
+            packed * CODE_ALPHABET.len() as u16
+                + digit(byte).expect("codes only contain alphabet bytes")
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("codes only contain ascii bytes")
+    }
+}
+
+/// The name of a node.
+///
+/// Almost every name is a [`NodeCode`], which needs no allocation; anything else is kept as-is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeName {
+    Code(NodeCode),
+    Other(Box<str>),
+}
+
+impl NodeName {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NodeName::Code(code) => code.as_str(),
+            NodeName::Other(name) => name,
+        }
+    }
+}
+
+impl FromStr for NodeName {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(NodeCode::new(s).map_or_else(|| NodeName::Other(s.into()), NodeName::Code))
+    }
+}
+
+impl fmt::Display for NodeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("000", Some(0))]
+    #[case("001", Some(1))]
+    #[case("00Z", Some(35))]
+    #[case("010", Some(36))]
+    #[case("ZZZ", Some(CODE_SPACE as u16 - 1))]
+    #[case("AAA", Some(10 * 36 * 36 + 10 * 36 + 10))]
+    #[case("aaa", None)]
+    #[case("AA", None)]
+    #[case("AAAA", None)]
+    #[case("A-A", None)]
+    fn packing(#[case] name: &str, #[case] expect: Option<u16>) {
+        assert_eq!(NodeCode::new(name).map(NodeCode::packed), expect);
+    }
+
+    #[rstest]
+    #[case("AAA")]
+    #[case("11Z")]
+    #[case("start")]
+    #[case("é")]
+    fn names_round_trip(#[case] name: &str) {
+        let parsed = name.parse::<NodeName>().unwrap();
+        assert_eq!(parsed.as_str(), name);
+        assert_eq!(parsed.to_string(), name);
+    }
+}
@@ -1,42 +1,56 @@
 use aoclib::parse;
+use num_bigint::BigInt;
+use num_traits::Zero;
 use std::{path::Path, str::FromStr};
 
-type Item = i32;
-type Sequence = Vec<Item>;
+pub mod polynomial;
+pub mod pyramid;
+pub mod stream;
+pub mod value;
 
-fn make_diffs(sequence: &Sequence) -> Sequence {
-    let mut diffs = Vec::with_capacity(sequence.len() - 1);
-    diffs.extend(sequence.windows(2).map(|window| {
-        let [left, right] = window
+use polynomial::Polynomial;
+use pyramid::{Highlight, Pyramid};
+use value::{checked_add, checked_sub, narrow, Value, Width};
+
+fn make_diffs<T: Value>(sequence: &[T]) -> Result<Vec<T>, Error> {
+    let mut diffs = Vec::with_capacity(sequence.len().saturating_sub(1));
+    for window in sequence.windows(2) {
+        let [left, right]: &[T; 2] = window
             .try_into()
             .expect("`windows(2)` produces windows of size 2");
-        right - left
-    }));
-    diffs
+        diffs.push(checked_sub(right, left)?);
+    }
+    Ok(diffs)
 }
 
-fn predict_next_value(sequence: &Sequence) -> Item {
-    let diffs = make_diffs(sequence);
+fn predict_next_value<T: Value>(sequence: &[T]) -> Result<T, Error> {
+    let diffs = make_diffs(sequence)?;
 
-    let next_diff = if diffs.iter().all(|&d| d == 0) {
-        0
+    let next_diff = if diffs.iter().all(Zero::is_zero) {
+        T::zero()
     } else {
-        predict_next_value(&diffs)
+        predict_next_value(&diffs)?
     };
 
-    sequence.last().copied().unwrap_or_default() + next_diff
+    let last = sequence
+        .last()
+        .expect("sequences are checked to be non-empty");
+    checked_add(last, &next_diff)
 }
 
-fn predict_prev_value(sequence: &Sequence) -> Item {
-    let diffs = make_diffs(sequence);
+fn predict_prev_value<T: Value>(sequence: &[T]) -> Result<T, Error> {
+    let diffs = make_diffs(sequence)?;
 
-    let prev_diff = if diffs.iter().all(|&d| d == 0) {
-        0
+    let prev_diff = if diffs.iter().all(Zero::is_zero) {
+        T::zero()
     } else {
-        predict_prev_value(&diffs)
+        predict_prev_value(&diffs)?
     };
 
-    sequence.first().copied().unwrap_or_default() - prev_diff
+    let first = sequence
+        .first()
+        .expect("sequences are checked to be non-empty");
+    checked_sub(first, &prev_diff)
 }
 
 // this should probably go into Aoclib
@@ -67,28 +81,260 @@ impl<T> SpaceSep<T> {
 #[error("failed to parse as space-separated line")]
 struct SpaceSepError<E>(#[from] E);
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let sequences = parse::<SpaceSep<Item>>(input)?
+/// Parse every sequence, and [check] that each can be extrapolated.
+///
+/// Values are parsed at arbitrary precision, to be narrowed later.
+pub fn sequences(input: &Path, strict: bool) -> Result<Vec<Vec<BigInt>>, Error> {
+    let sequences = parse::<SpaceSep<BigInt>>(input)?
         .map(SpaceSep::into_inner)
         .collect::<Vec<_>>();
-    let soev = sequences.iter().map(predict_next_value).sum::<Item>();
+    check(&sequences, strict)?;
+    Ok(sequences)
+}
+
+/// Check that every sequence can be extrapolated.
+///
+/// Sequences which are not polynomial within their length are refused in `strict` mode;
+/// otherwise they are extrapolated anyway, with a warning.
+fn check(sequences: &[Vec<BigInt>], strict: bool) -> Result<(), Error> {
+    for (idx, sequence) in sequences.iter().enumerate() {
+        let line = idx + 1;
+        match sequence.len() {
+            0 => return Err(Error::EmptySequence { line }),
+            1 => return Err(Error::SingleValue { line }),
+            _ => {}
+        }
+        let polynomial = Polynomial::fit(sequence);
+        if !polynomial.is_confirmed() {
+            let degree = polynomial.degree().unwrap_or_default();
+            if strict {
+                return Err(Error::NotPolynomial { line, degree });
+            }
+            eprintln!(
+                "warning: sequence {line} is not polynomial within its length; extrapolating its degree {degree} fit"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Which end of each sequence to extrapolate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    Next,
+    Prev,
+}
+
+/// Sum the extrapolated values at one end of every sequence, computing with values of type `T`.
+fn sum_extrapolated<T: Value>(sequences: &[Vec<BigInt>], end: End) -> Result<BigInt, Error> {
+    let mut sum = T::zero();
+    for sequence in sequences {
+        let sequence = sequence
+            .iter()
+            .cloned()
+            .map(narrow::<T>)
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = match end {
+            End::Next => predict_next_value(&sequence)?,
+            End::Prev => predict_prev_value(&sequence)?,
+        };
+        sum = checked_add(&sum, &value)?;
+    }
+    Ok(sum.into())
+}
+
+/// Sum the extrapolated values at one end of every sequence.
+///
+/// When no width is given, try each from narrowest to widest until one doesn't overflow.
+fn sum_with_width(
+    sequences: &[Vec<BigInt>],
+    end: End,
+    width: Option<Width>,
+) -> Result<BigInt, Error> {
+    let sum_as = |width| match width {
+        Width::I32 => sum_extrapolated::<i32>(sequences, end),
+        Width::I64 => sum_extrapolated::<i64>(sequences, end),
+        Width::I128 => sum_extrapolated::<i128>(sequences, end),
+        Width::Big => sum_extrapolated::<BigInt>(sequences, end),
+    };
+    if let Some(width) = width {
+        return sum_as(width);
+    }
+    for width in Width::ALL {
+        match sum_as(width) {
+            Err(Error::Overflow) => continue,
+            result => return result,
+        }
+    }
+    unreachable!("arbitrary precision never overflows")
+}
+
+pub fn part1(sequences: &[Vec<BigInt>], width: Option<Width>) -> Result<(), Error> {
+    let soev = sum_with_width(sequences, End::Next, width)?;
     println!("sum of extrapolated values (pt 1): {soev}");
     Ok(())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let sequences = parse::<SpaceSep<Item>>(input)?
-        .map(SpaceSep::into_inner)
-        .collect::<Vec<_>>();
-    let soev = sequences.iter().map(predict_prev_value).sum::<Item>();
+pub fn part2(sequences: &[Vec<BigInt>], width: Option<Width>) -> Result<(), Error> {
+    let soev = sum_with_width(sequences, End::Prev, width)?;
     println!("sum of extrapolated values (pt 2): {soev}");
     Ok(())
 }
 
+/// Sum the values `steps` positions after the end, and before the start, of every sequence.
+///
+/// Each sequence is extrapolated exactly through its fitted polynomial, so `steps` may be arbitrarily large.
+pub fn extrapolate(sequences: &[Vec<BigInt>], steps: u64) -> Result<(), Error> {
+    let polynomials = sequences
+        .iter()
+        .map(|sequence| Polynomial::fit(sequence))
+        .collect::<Vec<_>>();
+    let forward = polynomials
+        .iter()
+        .map(|polynomial| polynomial.forward(steps))
+        .sum::<BigInt>();
+    let backward = polynomials
+        .iter()
+        .map(|polynomial| polynomial.backward(steps))
+        .sum::<BigInt>();
+    println!("sum of values {steps} steps forward: {forward}");
+    println!("sum of values {steps} steps backward: {backward}");
+    Ok(())
+}
+
+/// Print the degree and coefficients of the polynomial fitted to each sequence, with `x` counting from 0 at its first value.
+///
+/// Fits which the sequence is too short to confirm are marked as such.
+pub fn fit(sequences: &[Vec<BigInt>]) -> Result<(), Error> {
+    for (idx, sequence) in sequences.iter().enumerate() {
+        let polynomial = Polynomial::fit(sequence);
+        let degree = polynomial
+            .degree()
+            .map_or_else(|| "zero".to_string(), |degree| format!("degree {degree}"));
+        let unconfirmed = if polynomial.is_confirmed() {
+            ""
+        } else {
+            " (unconfirmed)"
+        };
+        println!("line {}: {degree}: {polynomial}{unconfirmed}", idx + 1);
+    }
+    Ok(())
+}
+
+/// Print the difference pyramid of each selected sequence, numbered from 1, with its extrapolated values highlighted.
+pub fn show_pyramids(sequences: &[Vec<BigInt>], lines: &[usize]) -> Result<(), Error> {
+    let highlight = Highlight::detect();
+    for &line in lines {
+        let sequence = line
+            .checked_sub(1)
+            .and_then(|idx| sequences.get(idx))
+            .ok_or(Error::NoSuchSequence { line })?;
+        println!("sequence {line}:");
+        print!("{}", Pyramid::new(sequence)?.render(highlight));
+    }
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("parse error: {0}")]
+    Parse(String),
+    #[error("sequence {line} is empty")]
+    EmptySequence { line: usize },
+    #[error("sequence {line} has a single value, which cannot show a trend")]
+    SingleValue { line: usize },
+    #[error("sequence {line} is not polynomial within its length: only a degree {degree} polynomial fits it")]
+    NotPolynomial { line: usize, degree: usize },
+    #[error("there is no sequence {line}")]
+    NoSuchSequence { line: usize },
+    #[error("arithmetic overflow; try a wider value type")]
+    Overflow,
     #[error("no solution found")]
     NoSolution,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(sequences: &[&[i128]]) -> Vec<Vec<BigInt>> {
+        sequences
+            .iter()
+            .map(|sequence| sequence.iter().copied().map(BigInt::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn example() {
+        let sequences = big(&[
+            &[0, 3, 6, 9, 12, 15],
+            &[1, 3, 6, 10, 15, 21],
+            &[10, 13, 16, 21, 30, 45],
+        ]);
+        for width in Width::ALL {
+            assert_eq!(
+                sum_with_width(&sequences, End::Next, Some(width)).unwrap(),
+                BigInt::from(114)
+            );
+            assert_eq!(
+                sum_with_width(&sequences, End::Prev, Some(width)).unwrap(),
+                BigInt::from(2)
+            );
+        }
+    }
+
+    #[test]
+    fn degenerate_sequences() {
+        assert!(matches!(
+            check(&big(&[&[1, 2], &[]]), false),
+            Err(Error::EmptySequence { line: 2 })
+        ));
+        assert!(matches!(
+            check(&big(&[&[1, 2], &[3, 4], &[5]]), false),
+            Err(Error::SingleValue { line: 3 })
+        ));
+    }
+
+    #[test]
+    fn strict_mode() {
+        // the last two differences are both 1, but a degree 3 fit is needed to reach the first value
+        let sequences = big(&[&[0, 3, 6, 9], &[9, 0, 1, 2]]);
+        assert!(check(&sequences, false).is_ok());
+        assert!(matches!(
+            check(&sequences, true),
+            Err(Error::NotPolynomial { line: 2, degree: 3 })
+        ));
+        assert!(check(&big(&[&[0, 3, 6, 9], &[1, 3, 6, 10]]), true).is_ok());
+    }
+
+    #[test]
+    fn overflow_widens() {
+        // the values fit in an i32, but the next one doesn't
+        let max = i32::MAX as i128;
+        let sequences = big(&[&[max - 2, max - 1, max]]);
+        assert!(matches!(
+            sum_with_width(&sequences, End::Next, Some(Width::I32)),
+            Err(Error::Overflow)
+        ));
+        assert_eq!(
+            sum_with_width(&sequences, End::Next, None).unwrap(),
+            BigInt::from(max + 1)
+        );
+
+        // values which don't fit in an i128 at all
+        let sequences = vec![vec![
+            BigInt::from(i128::MAX) * 2,
+            BigInt::from(i128::MAX) * 3,
+        ]];
+        assert!(matches!(
+            sum_with_width(&sequences, End::Next, Some(Width::I128)),
+            Err(Error::Overflow)
+        ));
+        assert_eq!(
+            sum_with_width(&sequences, End::Next, None).unwrap(),
+            BigInt::from(i128::MAX) * 4
+        );
+    }
+}
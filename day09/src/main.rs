@@ -1,5 +1,8 @@
 use aoclib::{config::Config, website::get_input};
-use day09::{part1, part2};
+use day09::{
+    extrapolate, fit, part1, part2, sequences, show_pyramids, stream::stream, value::Width,
+};
+use num_bigint::BigInt;
 
 use clap::Parser;
 use color_eyre::eyre::Result;
@@ -15,6 +18,11 @@ struct RunArgs {
     /// when unset, defaults to `inputs/input-NN.txt`, where `NN` is the current day
     input: Option<PathBuf>,
 
+    /// instead of solving the puzzle, read a single sequence's readings from stdin,
+    /// predicting its next and previous values after each line
+    #[arg(long)]
+    stream: bool,
+
     /// skip part 1
     #[arg(long)]
     no_part1: bool,
@@ -22,6 +30,28 @@ struct RunArgs {
     /// run part 2
     #[arg(long)]
     part2: bool,
+
+    /// numeric type for values: i32, i64, i128, or big
+    ///
+    /// when unset, the narrowest which doesn't overflow; arbitrary precision when streaming
+    #[arg(long)]
+    width: Option<Width>,
+
+    /// refuse to extrapolate sequences which are not polynomial within their length
+    #[arg(long)]
+    strict: bool,
+
+    /// sum the values this many steps beyond each end of every sequence
+    #[arg(long, value_name = "STEPS")]
+    extrapolate: Option<u64>,
+
+    /// print the polynomial fitted to each sequence
+    #[arg(long)]
+    fit: bool,
+
+    /// print the difference pyramid of this sequence, numbered from 1; may be repeated
+    #[arg(long, value_name = "LINE")]
+    pyramid: Vec<usize>,
 }
 
 impl RunArgs {
@@ -42,13 +72,34 @@ impl RunArgs {
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = RunArgs::parse();
+    if args.stream {
+        let stdin = std::io::stdin().lock();
+        match args.width.unwrap_or(Width::Big) {
+            Width::I32 => stream::<i32>(stdin)?,
+            Width::I64 => stream::<i64>(stdin)?,
+            Width::I128 => stream::<i128>(stdin)?,
+            Width::Big => stream::<BigInt>(stdin)?,
+        }
+        return Ok(());
+    }
     let input_path = args.input()?;
+    // parse and check once, so that warnings aren't repeated for every mode
+    let sequences = sequences(&input_path, args.strict)?;
 
     if !args.no_part1 {
-        part1(&input_path)?;
+        part1(&sequences, args.width)?;
     }
     if args.part2 {
-        part2(&input_path)?;
+        part2(&sequences, args.width)?;
+    }
+    if let Some(steps) = args.extrapolate {
+        extrapolate(&sequences, steps)?;
+    }
+    if args.fit {
+        fit(&sequences)?;
+    }
+    if !args.pyramid.is_empty() {
+        show_pyramids(&sequences, &args.pyramid)?;
     }
     Ok(())
 }
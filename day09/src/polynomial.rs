@@ -0,0 +1,194 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+/// The polynomial of least degree through every value of a sequence, in Newton's forward-difference form.
+///
+/// The sequence's values are taken to be at `x = 0, 1, …, n - 1`. Then
+/// `p(x) = Σ Δʲy₀ · C(x, j)`, where `Δʲy₀` is the first value of the `j`th row of differences.
+/// Every `C(x, j)` is an integer for integer `x`, so extrapolation is exact however far it goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// How many values the polynomial was fit to.
+    len: usize,
+    /// The first value of each row of differences, up to the last which is nonzero.
+    differences: Vec<BigInt>,
+}
+
+impl Polynomial {
+    /// Fit a polynomial through every value of the sequence.
+    pub fn fit<T: Clone + Into<BigInt>>(sequence: &[T]) -> Self {
+        let mut row = sequence
+            .iter()
+            .map(|value| value.clone().into())
+            .collect::<Vec<BigInt>>();
+        let mut differences = Vec::with_capacity(row.len());
+        while let Some(first) = row.first() {
+            differences.push(first.clone());
+            row = row
+                .windows(2)
+                .map(|window| &window[1] - &window[0])
+                .collect();
+        }
+        while differences.last().is_some_and(Zero::is_zero) {
+            differences.pop();
+        }
+        Self {
+            len: sequence.len(),
+            differences,
+        }
+    }
+
+    /// The polynomial's degree, or `None` if it is zero everywhere.
+    pub fn degree(&self) -> Option<usize> {
+        self.differences.len().checked_sub(1)
+    }
+
+    /// Whether the sequence had more values than the polynomial needs, so that at least one row of differences was all zero.
+    ///
+    /// Any `n` values fit some polynomial of degree `n - 1`, so when this is `false`, the
+    /// sequence gives no evidence that it is polynomial at all.
+    pub fn is_confirmed(&self) -> bool {
+        self.differences.len() < self.len
+    }
+
+    /// The polynomial's value at `x`.
+    pub fn value_at(&self, x: &BigInt) -> BigInt {
+        let mut value = BigInt::zero();
+        // C(x, 0) = 1; C(x, j) = C(x, j - 1) · (x - j + 1) / j, and that division is exact
+        let mut binomial = BigInt::one();
+        for (j, difference) in self.differences.iter().enumerate() {
+            if j > 0 {
+                binomial = binomial * (x - (j - 1)) / j;
+            }
+            value += difference * &binomial;
+        }
+        value
+    }
+
+    /// The value `steps` positions after the sequence's last; `1` is the next value.
+    pub fn forward(&self, steps: u64) -> BigInt {
+        let last = BigInt::from(self.len) - 1;
+        self.value_at(&(last + steps))
+    }
+
+    /// The value `steps` positions before the sequence's first; `1` is the previous value.
+    pub fn backward(&self, steps: u64) -> BigInt {
+        self.value_at(&-BigInt::from(steps))
+    }
+
+    /// The polynomial's coefficients in the monomial basis, constant term first.
+    pub fn coefficients(&self) -> Vec<BigRational> {
+        let mut coefficients = vec![BigRational::zero(); self.differences.len()];
+        // the falling factorial x(x - 1)…(x - j + 1), constant term first
+        let mut falling = vec![BigInt::one()];
+        let mut factorial = BigInt::one();
+        for (j, difference) in self.differences.iter().enumerate() {
+            if j > 0 {
+                let offset = BigInt::from(j - 1);
+                let mut next = vec![BigInt::zero(); falling.len() + 1];
+                for (power, coefficient) in falling.iter().enumerate() {
+                    next[power + 1] += coefficient;
+                    next[power] -= coefficient * &offset;
+                }
+                falling = next;
+                factorial *= j;
+            }
+            for (power, coefficient) in falling.iter().enumerate() {
+                coefficients[power] +=
+                    BigRational::new(difference * coefficient, factorial.clone());
+            }
+        }
+        coefficients
+    }
+}
+
+/// Writes the polynomial highest power first, e.g. `1/2x^2 + 3/2x + 1`.
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coefficients = self.coefficients();
+        let mut wrote_any = false;
+        for (power, coefficient) in coefficients.iter().enumerate().rev() {
+            if coefficient.is_zero() {
+                continue;
+            }
+            let magnitude = coefficient.abs();
+            match (wrote_any, coefficient.is_negative()) {
+                (false, false) => {}
+                (false, true) => write!(f, "-")?,
+                (true, false) => write!(f, " + ")?,
+                (true, true) => write!(f, " - ")?,
+            }
+            if power == 0 || !magnitude.is_one() {
+                write!(f, "{magnitude}")?;
+            }
+            match power {
+                0 => {}
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{power}")?,
+            }
+            wrote_any = true;
+        }
+        if !wrote_any {
+            write!(f, "0")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [[i64; 6]; 3] = [
+        [0, 3, 6, 9, 12, 15],
+        [1, 3, 6, 10, 15, 21],
+        [10, 13, 16, 21, 30, 45],
+    ];
+
+    #[test]
+    fn one_step() {
+        let next = EXAMPLE.map(|sequence| Polynomial::fit(&sequence).forward(1));
+        assert_eq!(next, [18, 28, 68].map(BigInt::from));
+        let prev = EXAMPLE.map(|sequence| Polynomial::fit(&sequence).backward(1));
+        assert_eq!(prev, [-3, 0, 5].map(BigInt::from));
+    }
+
+    #[test]
+    fn coefficients() {
+        let polynomial = Polynomial::fit(&EXAMPLE[1]);
+        assert_eq!(polynomial.degree(), Some(2));
+        let half = |n: i64| BigRational::new(n.into(), 2.into());
+        assert_eq!(polynomial.coefficients(), vec![half(2), half(3), half(1)]);
+        assert_eq!(polynomial.to_string(), "1/2x^2 + 3/2x + 1");
+
+        assert_eq!(Polynomial::fit(&EXAMPLE[0]).to_string(), "3x");
+        assert_eq!(Polynomial::fit(&[5, 4, 3]).to_string(), "-x + 5");
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).degree(), None);
+        assert_eq!(Polynomial::fit(&[0, 0, 0]).to_string(), "0");
+    }
+
+    #[test]
+    fn confirmation() {
+        assert!(EXAMPLE
+            .iter()
+            .all(|sequence| Polynomial::fit(sequence).is_confirmed()));
+        // the third differences are a single nonzero value
+        assert!(!Polynomial::fit(&[1, 2, 4, 8]).is_confirmed());
+        assert!(Polynomial::fit(&[1, 2, 4, 8, 15]).is_confirmed());
+        assert!(!Polynomial::fit(&[7]).is_confirmed());
+        assert!(Polynomial::fit(&[0]).is_confirmed());
+    }
+
+    #[test]
+    fn far_extrapolation() {
+        // x^3, far beyond the range of any primitive integer
+        let cubes = Polynomial::fit(&[0, 1, 8, 27, 64]);
+        let steps = 10_u64.pow(18);
+        let x = BigInt::from(steps) + 4;
+        assert_eq!(cubes.forward(steps), &x * &x * &x);
+        assert_eq!(cubes.backward(steps), -BigInt::from(steps).pow(3));
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt::Write;
+
+use crate::{
+    make_diffs,
+    value::{checked_add, checked_sub, Value},
+    Error,
+};
+
+/// How extrapolated values are set apart from the sequence's own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Surround them with brackets.
+    Brackets,
+    /// Bold them with ANSI escapes, for terminals.
+    Ansi,
+}
+
+impl Highlight {
+    /// ANSI escapes if stdout is a terminal, brackets otherwise.
+    pub fn detect() -> Self {
+        if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+            Self::Ansi
+        } else {
+            Self::Brackets
+        }
+    }
+}
+
+/// A sequence's difference pyramid, extrapolated by one value at each end of every row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pyramid<T> {
+    /// The sequence, then each row of differences down to the first which is all zero.
+    rows: Vec<Vec<T>>,
+    /// The extrapolated value before each row.
+    prev: Vec<T>,
+    /// The extrapolated value after each row.
+    next: Vec<T>,
+}
+
+impl<T: Value> Pyramid<T> {
+    pub fn new(sequence: &[T]) -> Result<Self, Error> {
+        let mut rows = vec![sequence.to_vec()];
+        while let Some(row) = rows
+            .last()
+            .filter(|row| row.iter().any(|value| !value.is_zero()))
+        {
+            rows.push(make_diffs(row)?);
+        }
+
+        // the last row is all zero, so extends with zeros
+        let mut prev = vec![T::zero(); rows.len()];
+        let mut next = vec![T::zero(); rows.len()];
+        for idx in (0..rows.len() - 1).rev() {
+            let row = &rows[idx];
+            prev[idx] = checked_sub(&row[0], &prev[idx + 1])?;
+            next[idx] = checked_add(&row[row.len() - 1], &next[idx + 1])?;
+        }
+        Ok(Self { rows, prev, next })
+    }
+
+    /// Lay the pyramid out as in the puzzle text, each row offset by half a cell from the one above.
+    pub fn render(&self, highlight: Highlight) -> String {
+        let extrapolated = |value: &T| match highlight {
+            Highlight::Brackets => (format!("[{value}]"), format!("[{value}]")),
+            Highlight::Ansi => (format!("\x1b[1;33m{value}\x1b[0m"), value.to_string()),
+        };
+        // each cell is (text, visible text)
+        let rows = self
+            .rows
+            .iter()
+            .zip(self.prev.iter().zip(&self.next))
+            .map(|(row, (prev, next))| {
+                std::iter::once(extrapolated(prev))
+                    .chain(
+                        row.iter()
+                            .map(|value| (value.to_string(), value.to_string())),
+                    )
+                    .chain(std::iter::once(extrapolated(next)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let widest = rows
+            .iter()
+            .flatten()
+            .map(|(_text, visible)| visible.len())
+            .max()
+            .unwrap_or_default();
+        // at least one space between cells, and even, so that rows can be offset by half a cell
+        let cell_width = (widest + 2) & !1;
+
+        let mut out = String::new();
+        for (depth, row) in rows.iter().enumerate() {
+            let mut line = " ".repeat(depth * cell_width / 2);
+            for (text, visible) in row {
+                let padding = cell_width - visible.len();
+                let _ = write!(line, "{}{text}", " ".repeat(padding));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let pyramid = Pyramid::new(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(
+            pyramid.render(Highlight::Brackets),
+            "   [5]    10    13    16    21    30    45  [68]
+      [5]     3     3     5     9    15  [23]
+        [-2]     0     2     4     6   [8]
+            [2]     2     2     2   [2]
+               [0]     0     0   [0]
+"
+        );
+    }
+
+    #[test]
+    fn never_reaches_zero() {
+        // the last row of differences has a single value, which extends as a constant
+        let pyramid = Pyramid::new(&[1, 2, 4, 8]).unwrap();
+        assert_eq!(pyramid.prev, vec![0, 1, 0, 1, 0]);
+        assert_eq!(pyramid.next, vec![15, 7, 3, 1, 0]);
+        assert_eq!(pyramid.rows.last(), Some(&Vec::new()));
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(matches!(
+            Pyramid::new(&[i32::MIN, i32::MAX]),
+            Err(Error::Overflow)
+        ));
+    }
+}
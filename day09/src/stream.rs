@@ -0,0 +1,170 @@
+use std::io::BufRead;
+
+use num_bigint::BigInt;
+
+use crate::{
+    value::{checked_add, checked_sub, narrow, Value},
+    Error,
+};
+
+/// The difference pyramid of a sequence which arrives one value at a time.
+///
+/// Only the ends of each row of differences are kept: extending the pyramid downwards
+/// by one new value only touches the last value of each row, and the first values never
+/// change once written. Each push therefore costs one subtraction per row, rather than
+/// rebuilding the whole pyramid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceTable<T> {
+    /// The first value of each row; row 0 is the sequence itself.
+    heads: Vec<T>,
+    /// The last value of each row.
+    tails: Vec<T>,
+}
+
+impl<T> Default for DifferenceTable<T> {
+    fn default() -> Self {
+        Self {
+            heads: Vec::new(),
+            tails: Vec::new(),
+        }
+    }
+}
+
+impl<T: Value> DifferenceTable<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many values have been pushed.
+    pub fn len(&self) -> usize {
+        self.tails.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tails.is_empty()
+    }
+
+    /// Append a value to the sequence, updating every row of differences.
+    ///
+    /// On overflow, the table is left as it was.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        let mut tails = Vec::with_capacity(self.tails.len() + 1);
+        let mut difference = value;
+        for tail in &self.tails {
+            let next_difference = checked_sub(&difference, tail)?;
+            tails.push(difference);
+            difference = next_difference;
+        }
+        // every push adds a row, holding a single value
+        tails.push(difference.clone());
+        self.heads.push(difference);
+        self.tails = tails;
+        Ok(())
+    }
+
+    /// The value which would come next, as if the last row of differences stayed constant.
+    ///
+    /// `None` until a value has been pushed.
+    pub fn next_value(&self) -> Result<Option<T>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        self.tails
+            .iter()
+            .try_fold(T::zero(), |sum, tail| checked_add(&sum, tail))
+            .map(Some)
+    }
+
+    /// The value which would have come before the first, as if the last row of differences stayed constant.
+    ///
+    /// `None` until a value has been pushed.
+    pub fn prev_value(&self) -> Result<Option<T>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        self.heads
+            .iter()
+            .rev()
+            .try_fold(T::zero(), |prev_difference, head| {
+                checked_sub(head, &prev_difference)
+            })
+            .map(Some)
+    }
+}
+
+/// Read a single sequence as a stream of whitespace-separated readings, printing the predicted
+/// next and previous values after each line of readings.
+pub fn stream<T: Value>(reader: impl BufRead) -> Result<(), Error> {
+    let mut table = DifferenceTable::<T>::new();
+    for line in reader.lines() {
+        for reading in line?.split_ascii_whitespace() {
+            let value = reading
+                .parse::<BigInt>()
+                .map_err(|err| Error::Parse(format!("reading \"{reading}\": {err}")))?;
+            table.push(narrow(value)?)?;
+        }
+        if let (Some(next), Some(prev)) = (table.next_value()?, table.prev_value()?) {
+            println!(
+                "after {} readings: next {next}, previous {prev}",
+                table.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{predict_next_value, predict_prev_value};
+
+    const EXAMPLE: [[i32; 6]; 3] = [
+        [0, 3, 6, 9, 12, 15],
+        [1, 3, 6, 10, 15, 21],
+        [10, 13, 16, 21, 30, 45],
+    ];
+
+    fn table(sequence: &[i32]) -> DifferenceTable<i32> {
+        let mut table = DifferenceTable::new();
+        for &value in sequence {
+            table.push(value).unwrap();
+        }
+        table
+    }
+
+    #[test]
+    fn example() {
+        let next = EXAMPLE.map(|sequence| table(&sequence).next_value().unwrap());
+        assert_eq!(next, [18, 28, 68].map(Some));
+        let prev = EXAMPLE.map(|sequence| table(&sequence).prev_value().unwrap());
+        assert_eq!(prev, [-3, 0, 5].map(Some));
+    }
+
+    #[test]
+    fn every_prefix_matches_pyramid() {
+        for sequence in EXAMPLE {
+            let mut table = DifferenceTable::new();
+            assert_eq!(table.next_value().unwrap(), None);
+            for len in 1..=sequence.len() {
+                table.push(sequence[len - 1]).unwrap();
+                let prefix = &sequence[..len];
+                assert_eq!(
+                    table.next_value().unwrap(),
+                    Some(predict_next_value(prefix).unwrap())
+                );
+                assert_eq!(
+                    table.prev_value().unwrap(),
+                    Some(predict_prev_value(prefix).unwrap())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overflow_leaves_table_unchanged() {
+        let mut table = table(&[0, i32::MAX]);
+        let before = table.clone();
+        assert!(matches!(table.push(i32::MIN), Err(Error::Overflow)));
+        assert_eq!(table, before);
+    }
+}
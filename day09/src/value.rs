@@ -0,0 +1,82 @@
+use std::{fmt, str::FromStr};
+
+use num_bigint::BigInt;
+use num_traits::{CheckedAdd, CheckedSub, Zero};
+
+use crate::Error;
+
+/// A numeric type which sequences' values may be held in.
+///
+/// All arithmetic on values is checked, so that overflow is reported rather than wrapping or panicking.
+pub trait Value:
+    Clone + fmt::Display + PartialEq + Zero + CheckedAdd + CheckedSub + Into<BigInt> + TryFrom<BigInt>
+{
+}
+
+impl<T> Value for T where
+    T: Clone
+        + fmt::Display
+        + PartialEq
+        + Zero
+        + CheckedAdd
+        + CheckedSub
+        + Into<BigInt>
+        + TryFrom<BigInt>
+{
+}
+
+pub(crate) fn checked_add<T: Value>(left: &T, right: &T) -> Result<T, Error> {
+    left.checked_add(right).ok_or(Error::Overflow)
+}
+
+pub(crate) fn checked_sub<T: Value>(left: &T, right: &T) -> Result<T, Error> {
+    left.checked_sub(right).ok_or(Error::Overflow)
+}
+
+/// Convert a value to `T`, failing with [`Error::Overflow`] if it doesn't fit.
+pub(crate) fn narrow<T: Value>(value: BigInt) -> Result<T, Error> {
+    T::try_from(value).map_err(|_| Error::Overflow)
+}
+
+/// Which numeric type to hold values in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    I32,
+    I64,
+    I128,
+    /// Arbitrary precision, which never overflows.
+    Big,
+}
+
+impl Width {
+    /// Every width, narrowest first.
+    pub const ALL: [Width; 4] = [Width::I32, Width::I64, Width::I128, Width::Big];
+}
+
+impl FromStr for Width {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "i32" => Ok(Self::I32),
+            "i64" => Ok(Self::I64),
+            "i128" => Ok(Self::I128),
+            "big" => Ok(Self::Big),
+            _ => Err(Error::Parse(format!(
+                "unknown width \"{s}\"; expected one of i32, i64, i128, big"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::I128 => "i128",
+            Self::Big => "big",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,58 @@
+use aoclib::geometry::Point;
+
+/// Twice the area of the polygon with these vertices, in order, by the shoelace formula.
+///
+/// The polygon is closed implicitly: the last vertex connects back to the first.
+fn double_area(vertices: &[Point]) -> i64 {
+    let Some(&last) = vertices.last() else {
+        return 0;
+    };
+    let mut previous = last;
+    let mut sum = 0_i64;
+    for &vertex in vertices {
+        sum += previous.x as i64 * vertex.y as i64 - vertex.x as i64 * previous.y as i64;
+        previous = vertex;
+    }
+    sum.abs()
+}
+
+/// Count the tiles enclosed by a loop, given every tile of the loop in order.
+///
+/// Each loop tile's centre is a lattice point on the polygon's boundary, and there are no
+/// others, so by Pick's theorem (`A = I + B/2 - 1`) the number of enclosed tiles is
+/// `I = A - B/2 + 1`.
+pub(crate) fn enclosed_tiles(path: &[Point]) -> usize {
+    let boundary = path.len() as i64;
+    let interior = (double_area(path) - boundary) / 2 + 1;
+    interior.max(0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(side: i32) -> Vec<Point> {
+        let mut path = Vec::new();
+        path.extend((0..side).map(|x| Point::new(x, 0)));
+        path.extend((0..side).map(|y| Point::new(side, y)));
+        path.extend((1..=side).rev().map(|x| Point::new(x, side)));
+        path.extend((1..=side).rev().map(|y| Point::new(0, y)));
+        path
+    }
+
+    #[test]
+    fn squares() {
+        // the smallest loop encloses nothing
+        assert_eq!(enclosed_tiles(&square(1)), 0);
+        assert_eq!(enclosed_tiles(&square(2)), 1);
+        assert_eq!(enclosed_tiles(&square(5)), 16);
+        assert_eq!(double_area(&square(5)), 50);
+    }
+
+    #[test]
+    fn orientation_does_not_matter() {
+        let mut path = square(4);
+        path.reverse();
+        assert_eq!(enclosed_tiles(&path), 9);
+    }
+}
@@ -3,6 +3,10 @@ use std::path::Path;
 use aoclib::geometry::{tile::DisplayWidth, Direction, Point};
 use strum::IntoEnumIterator as _;
 
+mod area;
+mod loops;
+mod render;
+
 type Map = aoclib::geometry::Map<Tile>;
 
 #[derive(
@@ -33,6 +37,11 @@ enum Tile {
 }
 
 impl Tile {
+    /// The directions in which this tile's pipe has ends; none unless it is a pipe.
+    fn ends(self) -> impl Iterator<Item = Direction> {
+        Direction::iter().filter(move |&direction| self.trace(direction).is_some())
+    }
+
     fn trace(self, direction: Direction) -> Option<Direction> {
         match (self, direction) {
             (Self::Vertical, Direction::Down) => Some(Direction::Up),
@@ -103,18 +112,6 @@ fn trace_path(
     }))
 }
 
-/// Find the path length of a successful complete traversal of a path.
-///
-/// `None` if any trace error occurs.
-fn path_length(map: &Map, initial: Point, direction: Direction) -> Option<usize> {
-    let mut steps = 0;
-    for (s, maybe_position) in trace_path(map, initial, direction).enumerate() {
-        maybe_position.ok()?;
-        steps = s;
-    }
-    Some(steps)
-}
-
 /// Replace the start point with an appropriate tile.
 ///
 /// This is a destructive edit; ensure you have retained the start point elsewhere.
@@ -138,32 +135,16 @@ fn replace_start_tile(map: &mut Map, initial: Point, direction: Direction) -> bo
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    let map = <Map as TryFrom<&Path>>::try_from(input)?;
-
-    let start_points = map
-        .iter()
-        .filter_map(|(position, tile)| tile.is_start().then_some(position))
-        .collect::<Vec<_>>();
-    let [start] = start_points.as_slice() else {
-        eprintln!("could not determine unique start point");
-        return Err(Error::NoSolution);
-    };
-
-    // A valid start position must have exactly two connections... but we don't know which two those are.
-    // So just try all of them.
-    let Some(path_len) =
-        Direction::iter().find_map(|direction| path_length(&map, *start, direction))
-    else {
-        eprintln!("no valid circuit found from start point");
-        return Err(Error::NoSolution);
-    };
+    let mut map = <Map as TryFrom<&Path>>::try_from(input)?;
+    let (_start, path) = trace_main_loop(&mut map)?;
 
-    if path_len % 2 != 1 {
+    if !path.len().is_multiple_of(2) {
         eprintln!("no unique farthest point determinable");
         return Err(Error::NoSolution);
     }
 
-    let steps_to_farthest = (path_len + 1) / 2;
+    // the same point as `Analysis::farthest`
+    let steps_to_farthest = path.len() / 2;
     println!("steps to farthest (pt 1): {steps_to_farthest}");
     Ok(())
 }
@@ -255,9 +236,27 @@ fn flood_inside(map: &Map, tile_styles: &mut StyleMap) {
     }
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let mut map = <Map as TryFrom<&Path>>::try_from(input)?;
+/// A map with its main loop traced, and every other tile classified as inside or outside of it.
+struct Analysis {
+    /// The map, with the start tile replaced by the pipe it stands for.
+    map: Map,
+    start: Point,
+    /// Every point of the main loop, in order from the start.
+    path: Vec<Point>,
+    tile_styles: StyleMap,
+}
 
+/// Replace the start point with the pipe it stands for, if it is on a loop.
+///
+/// Returns the direction in which to leave the start to traverse that loop.
+fn resolve_start(map: &mut Map, start: Point) -> Option<Direction> {
+    Direction::iter().find(|&direction| replace_start_tile(map, start, direction))
+}
+
+/// Find the map's unique start point, replace it with the pipe it stands for, and trace the loop through it.
+///
+/// Returns the start, and every point of the loop in order from it.
+fn trace_main_loop(map: &mut Map) -> Result<(Point, Vec<Point>), Error> {
     let start_points = map
         .iter()
         .filter_map(|(position, tile)| tile.is_start().then_some(position))
@@ -266,30 +265,107 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         eprintln!("could not determine unique start point");
         return Err(Error::NoSolution);
     };
+    let start = *start;
 
-    // determine initial loop direction
-    let Some(initial_direction) =
-        Direction::iter().find(|&direction| replace_start_tile(&mut map, *start, direction))
-    else {
+    let Some(initial_direction) = resolve_start(map, start) else {
         eprintln!("no valid circuit found from start point");
         return Err(Error::NoSolution);
     };
 
-    let mut tile_styles = map.clone().convert_tile_type::<TileStyle>();
-    for item in trace_path(&map, *start, initial_direction) {
-        let point = item.expect("we had a valid trace of this loop earlier").0;
-        tile_styles[point] = TileStyle::MainLoop;
+    let path = trace_path(map, start, initial_direction)
+        .map(|item| item.expect("we had a valid trace of this loop earlier").0)
+        .collect();
+    Ok((start, path))
+}
+
+impl Analysis {
+    fn new(mut map: Map) -> Result<Self, Error> {
+        let (start, path) = trace_main_loop(&mut map)?;
+
+        let mut tile_styles = map.clone().convert_tile_type::<TileStyle>();
+        for &point in &path {
+            tile_styles[point] = TileStyle::MainLoop;
+        }
+
+        flood_inside(&map, &mut tile_styles);
+
+        debug_assert!(!tile_styles.iter().any(|(_point, tile)| tile.is_unknown()));
+
+        Ok(Self {
+            map,
+            start,
+            path,
+            tile_styles,
+        })
     }
 
-    flood_inside(&map, &mut tile_styles);
+    /// The point of the loop farthest from the start.
+    fn farthest(&self) -> Point {
+        self.path[self.path.len() / 2]
+    }
+}
 
-    debug_assert!(!tile_styles.iter().any(|(_point, tile)| tile.is_unknown()));
+/// Count the enclosed tiles by flooding, and cross-check that count against the loop's area.
+///
+/// With `fast`, skip the flood and count from the area alone.
+pub fn part2(input: &Path, fast: bool) -> Result<(), Error> {
+    let mut map = <Map as TryFrom<&Path>>::try_from(input)?;
+    if fast {
+        let (_start, path) = trace_main_loop(&mut map)?;
+        let enclosed = area::enclosed_tiles(&path);
+        println!("n enclosed tiles (pt 2): {enclosed}");
+        return Ok(());
+    }
 
-    let enclosed = tile_styles
+    let analysis = Analysis::new(map)?;
+    let flood = analysis
+        .tile_styles
         .iter()
         .filter(|(_point, tile)| tile.is_inside())
         .count();
-    println!("n enclosed tiles (pt 2): {enclosed}");
+    println!("n enclosed tiles (pt 2): {flood}");
+    let shoelace = area::enclosed_tiles(&analysis.path);
+    println!("n enclosed tiles by shoelace and Pick's theorem (pt 2): {shoelace}");
+    if flood != shoelace {
+        return Err(Error::AreaMismatch { flood, shoelace });
+    }
+    Ok(())
+}
+
+/// Draw the map with box-drawing glyphs, showing the main loop, which tiles it encloses, and its start and farthest points.
+pub fn render(input: &Path) -> Result<(), Error> {
+    let map = <Map as TryFrom<&Path>>::try_from(input)?;
+    let analysis = Analysis::new(map)?;
+    print!("{}", render::render(&analysis, render::Palette::detect()));
+    Ok(())
+}
+
+/// Print every closed loop of pipes in the map, whether or not it passes through the start.
+///
+/// Rows count down from the top of the map, and columns from its left, both from 0.
+pub fn all_loops(input: &Path) -> Result<(), Error> {
+    let map = <Map as TryFrom<&Path>>::try_from(input)?;
+    let top = map.top_left().y;
+    let loops = loops::find_loops(map);
+    println!("closed loops: {}", loops.len());
+    for (idx, pipe_loop) in loops.iter().enumerate() {
+        let (min, max) = pipe_loop.bounding_box();
+        let start = if pipe_loop.through_start {
+            " (through S)"
+        } else {
+            ""
+        };
+        println!(
+            "  loop {}{start}: length {}, rows {}..={}, columns {}..={}, {} enclosed tiles",
+            idx + 1,
+            pipe_loop.path.len(),
+            top - max.y,
+            top - min.y,
+            min.x,
+            max.x,
+            pipe_loop.enclosed_tiles(),
+        );
+    }
     Ok(())
 }
 
@@ -297,6 +373,101 @@ pub fn part2(input: &Path) -> Result<(), Error> {
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("flooding found {flood} enclosed tiles, but the loop's area implies {shoelace}")]
+    AreaMismatch { flood: usize, shoelace: usize },
     #[error("no solution found")]
     NoSolution,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flood fill and the shoelace formula must both find the puzzle's answers.
+    fn check_enclosed(map: &str, expect: usize) {
+        let map = <Map as TryFrom<&str>>::try_from(map).unwrap();
+        let analysis = Analysis::new(map).unwrap();
+        let flood = analysis
+            .tile_styles
+            .iter()
+            .filter(|(_point, tile)| tile.is_inside())
+            .count();
+        assert_eq!(flood, expect);
+        assert_eq!(area::enclosed_tiles(&analysis.path), expect);
+    }
+
+    #[test]
+    fn pt2_example_open() {
+        check_enclosed(
+            "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+",
+            4,
+        );
+    }
+
+    #[test]
+    fn pt2_example_squeezed() {
+        check_enclosed(
+            "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+",
+            4,
+        );
+    }
+
+    #[test]
+    fn pt2_example_larger() {
+        check_enclosed(
+            "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+",
+            8,
+        );
+    }
+
+    #[test]
+    fn pt2_example_junk() {
+        // here the start stands in for a `7`, and there is plenty of pipe outside the loop
+        check_enclosed(
+            "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+",
+            10,
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use aoclib::geometry::Point;
+
+use crate::{area, resolve_start, trace_path, Map};
+
+/// A closed loop of pipes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PipeLoop {
+    /// Every point of the loop, in order.
+    pub(crate) path: Vec<Point>,
+    /// Whether the loop passes through a start tile.
+    pub(crate) through_start: bool,
+}
+
+impl PipeLoop {
+    /// The lowest and highest coordinates of the loop's points.
+    pub(crate) fn bounding_box(&self) -> (Point, Point) {
+        self.path.iter().fold(
+            (
+                Point::new(i32::MAX, i32::MAX),
+                Point::new(i32::MIN, i32::MIN),
+            ),
+            |(min, max), point| {
+                (
+                    Point::new(min.x.min(point.x), min.y.min(point.y)),
+                    Point::new(max.x.max(point.x), max.y.max(point.y)),
+                )
+            },
+        )
+    }
+
+    /// How many tiles the loop encloses, including any which belong to loops nested inside it.
+    pub(crate) fn enclosed_tiles(&self) -> usize {
+        area::enclosed_tiles(&self.path)
+    }
+}
+
+/// Follow the pipes from `start`, marking every point reached as visited.
+///
+/// `None` unless the pipes lead back into `start` through its other end. Also `None` as soon
+/// as the pipes reach a point which was already visited: `start` was not, so that point's
+/// chain of pipes was traced earlier without closing into a loop.
+fn trace_loop(
+    map: &Map,
+    start: Point,
+    visited: &mut aoclib::geometry::Map<bool>,
+) -> Option<Vec<Point>> {
+    let tile = map[start];
+    // leave through either end of the pipe; the loop, if any, is the same
+    let exit = tile.ends().next()?;
+
+    let mut path = Vec::new();
+    let mut last_direction = exit;
+    for item in trace_path(map, start, exit) {
+        let (point, direction) = item.ok()?;
+        if visited[point] {
+            return None;
+        }
+        visited[point] = true;
+        path.push(point);
+        last_direction = direction;
+    }
+    // coming back into the start, we must arrive at its other end
+    (tile.trace(last_direction.reverse()) == Some(exit)).then_some(path)
+}
+
+/// Find every closed loop of pipes in the map, longest first.
+///
+/// Each start tile is replaced by the pipe it stands for, where there is one, so that its loop
+/// is found along with the rest; no start tile is required.
+pub(crate) fn find_loops(mut map: Map) -> Vec<PipeLoop> {
+    let starts = map
+        .iter()
+        .filter_map(|(position, tile)| tile.is_start().then_some(position))
+        .collect::<Vec<_>>();
+    for &start in &starts {
+        // unresolvable starts are left in place, and connect to nothing
+        let _ = resolve_start(&mut map, start);
+    }
+
+    let mut visited = aoclib::geometry::Map::<bool>::new(map.width(), map.height());
+    let mut loops = Vec::new();
+    for (point, _tile) in map.iter() {
+        if visited[point] {
+            continue;
+        }
+        // every pipe has exactly two ends, so each point is on at most one chain of pipes.
+        // a loop is marked entirely by the first trace through it, and a trace along a broken
+        // chain stops at the first point marked by an earlier one, so each point is traced
+        // only a bounded number of times
+        if let Some(path) = trace_loop(&map, point, &mut visited) {
+            let through_start = path.iter().any(|point| starts.contains(point));
+            loops.push(PipeLoop {
+                path,
+                through_start,
+            });
+        }
+    }
+
+    loops.sort_by_key(|pipe_loop| std::cmp::Reverse(pipe_loop.path.len()));
+    loops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(s: &str) -> Map {
+        <Map as TryFrom<&str>>::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn without_start() {
+        let loops = find_loops(map("\
+F--7.F7
+|..|.LJ
+|..|-7.
+L--J.|.
+"));
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].path.len(), 12);
+        assert_eq!(loops[0].enclosed_tiles(), 4);
+        assert_eq!(
+            loops[0].bounding_box(),
+            (Point::new(0, 0), Point::new(3, 3))
+        );
+        assert!(!loops[0].through_start);
+        assert_eq!(loops[1].path.len(), 4);
+        assert_eq!(loops[1].enclosed_tiles(), 0);
+        assert_eq!(
+            loops[1].bounding_box(),
+            (Point::new(5, 2), Point::new(6, 3))
+        );
+    }
+
+    #[test]
+    fn with_start_and_broken_pipes() {
+        let loops = find_loops(map("\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF
+"));
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].path.len(), 8);
+        assert!(loops[0].through_start);
+        assert_eq!(loops[0].enclosed_tiles(), 1);
+    }
+
+    #[test]
+    fn nested() {
+        let loops = find_loops(map("\
+F----7
+|F--7|
+|L--J|
+L----J
+"));
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].enclosed_tiles(), 8);
+        assert_eq!(loops[1].enclosed_tiles(), 0);
+    }
+
+    #[test]
+    fn broken_chains() {
+        // long chains which never close, traced from several points along each
+        let loops = find_loops(map("\
+F--------7.
+|.F7.F--.|.
+L-JL-J...L-
+"));
+        assert!(loops.is_empty());
+
+        let loops = find_loops(map("\
+F--------7.
+|.F7.F--7|.
+L-JL-J..LJ.
+"));
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].path.len(), 26);
+    }
+}
@@ -1,5 +1,5 @@
 use aoclib::{config::Config, website::get_input};
-use day10::{part1, part2};
+use day10::{all_loops, part1, part2, render};
 
 use clap::Parser;
 use color_eyre::eyre::Result;
@@ -22,6 +22,22 @@ struct RunArgs {
     /// run part 2
     #[arg(long)]
     part2: bool,
+
+    /// count part 2's enclosed tiles from the loop's area alone, without flooding or cross-checking
+    #[arg(long)]
+    fast: bool,
+
+    /// draw the map with box-drawing glyphs, highlighting the main loop and the tiles it encloses
+    ///
+    /// colours are used only when writing to a terminal
+    #[arg(long)]
+    render: bool,
+
+    /// list every closed loop of pipes, with its length, bounding box, and enclosed tiles
+    ///
+    /// unlike the puzzle parts, this doesn't need a start tile; combine with `--no-part1` for maps without one
+    #[arg(long)]
+    loops: bool,
 }
 
 impl RunArgs {
@@ -48,7 +64,13 @@ fn main() -> Result<()> {
         part1(&input_path)?;
     }
     if args.part2 {
-        part2(&input_path)?;
+        part2(&input_path, args.fast)?;
+    }
+    if args.render {
+        render(&input_path)?;
+    }
+    if args.loops {
+        all_loops(&input_path)?;
     }
     Ok(())
 }
@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use aoclib::geometry::Point;
+
+use crate::{Analysis, Tile, TileStyle};
+
+/// How to set tiles apart from each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Palette {
+    /// Glyphs alone, for plain-text files.
+    Plain,
+    /// Glyphs and ANSI colours, for terminals.
+    Ansi,
+}
+
+impl Palette {
+    /// ANSI colours if stdout is a terminal, plain glyphs otherwise.
+    pub(crate) fn detect() -> Self {
+        if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+            Self::Ansi
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_GREEN: &str = "\x1b[1;32m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const DIM: &str = "\x1b[2m";
+const INSIDE_BACKGROUND: &str = "\x1b[42m";
+const RESET: &str = "\x1b[0m";
+
+/// The glyph for a pipe which is part of the main loop.
+fn heavy(tile: Tile) -> char {
+    match tile {
+        Tile::Vertical => '┃',
+        Tile::Horizontal => '━',
+        Tile::L => '┗',
+        Tile::J => '┛',
+        Tile::Seven => '┓',
+        Tile::F => '┏',
+        Tile::Ground | Tile::Start => unreachable!("the main loop is made of pipes"),
+    }
+}
+
+/// The glyph for a tile which isn't part of the main loop.
+fn light(tile: Tile) -> char {
+    match tile {
+        Tile::Vertical => '│',
+        Tile::Horizontal => '─',
+        Tile::L => '└',
+        Tile::J => '┘',
+        Tile::Seven => '┐',
+        Tile::F => '┌',
+        Tile::Ground | Tile::Start => '·',
+    }
+}
+
+/// Draw the analysed map, top row first.
+///
+/// The main loop is drawn with heavy lines, with `S` at its start and `*` at its farthest point;
+/// everything else is drawn with light lines. In plain text, enclosed tiles are drawn as `I`;
+/// with ANSI colours, they keep their glyphs on a green background and tiles outside the loop are dimmed.
+pub(crate) fn render(analysis: &Analysis, palette: Palette) -> String {
+    let map = &analysis.map;
+    let farthest = analysis.farthest();
+    let mut out = String::new();
+    for y in (map.bottom_left().y..=map.top_right().y).rev() {
+        for x in map.bottom_left().x..=map.top_right().x {
+            let point = Point::new(x, y);
+            let tile = map[point];
+            let (glyph, colour) = match analysis.tile_styles[point] {
+                TileStyle::MainLoop if point == analysis.start => ('S', BOLD_GREEN),
+                TileStyle::MainLoop if point == farthest => ('*', BOLD_RED),
+                TileStyle::MainLoop => (heavy(tile), BOLD_YELLOW),
+                TileStyle::Inside => match palette {
+                    Palette::Plain => ('I', ""),
+                    Palette::Ansi => (light(tile), INSIDE_BACKGROUND),
+                },
+                TileStyle::Outside | TileStyle::Unknown => (light(tile), DIM),
+            };
+            match palette {
+                Palette::Plain => out.push(glyph),
+                Palette::Ansi => {
+                    let _ = write!(out, "{colour}{glyph}{RESET}");
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Map;
+
+    const EXAMPLE: &str = "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+";
+
+    fn analysis() -> Analysis {
+        Analysis::new(<Map as TryFrom<&str>>::try_from(EXAMPLE).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn plain_example() {
+        let analysis = analysis();
+        assert_eq!(
+            render(&analysis, Palette::Plain),
+            "\
+··········
+·S━━━━━━┓·
+·┃┏━━━━┓┃·
+·┃┃····*┃·
+·┃┃····┃┃·
+·┃┗━┓┏━┛┃·
+·┃II┃┃II┃·
+·┗━━┛┗━━┛·
+··········
+"
+        );
+    }
+
+    #[test]
+    fn ansi_example() {
+        let rendered = render(&analysis(), Palette::Ansi);
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with(&format!("{DIM}·{RESET}{BOLD_GREEN}S{RESET}")));
+        assert!(lines[3].contains(&format!("{BOLD_RED}*{RESET}")));
+        assert_eq!(
+            lines[6]
+                .matches(&format!("{INSIDE_BACKGROUND}·{RESET}"))
+                .count(),
+            4
+        );
+        assert_eq!(rendered.matches(INSIDE_BACKGROUND).count(), 4);
+    }
+}